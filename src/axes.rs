@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::{FACE_DIRECTIONS, PanOrbitState, PlanetSettings};
+
+/// How far the face normal arrows reach from the planet center, in planet radii.
+const ARROW_LENGTH: f32 = 1.6;
+
+/// A resource toggling the labeled face normal arrows.
+#[derive(Resource, Default, Debug)]
+pub struct FaceAxes {
+    pub visible: bool,
+}
+
+pub struct FaceAxesPlugin;
+
+impl Plugin for FaceAxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FaceAxes>()
+            .add_systems(Update, draw_face_axes)
+            .add_systems(EguiPrimaryContextPass, label_face_axes);
+    }
+}
+
+/// Returns the label of a face normal, e.g. "+X" or "-Z".
+fn axis_label(normal: Vec3) -> String {
+    let sign = if normal.max_element() > 0.0 { '+' } else { '-' };
+    let axis = match normal.abs() {
+        Vec3::X => 'X',
+        Vec3::Y => 'Y',
+        _ => 'Z',
+    };
+    format!("{sign}{axis}")
+}
+
+/// Returns the red, green or blue color of the axis a face normal lies on.
+fn axis_color(normal: Vec3) -> Srgba {
+    let abs = normal.abs();
+    Srgba::rgb(abs.x, abs.y, abs.z)
+}
+
+fn draw_face_axes(mut gizmos: Gizmos, face_axes: Res<FaceAxes>, settings: Res<PlanetSettings>) {
+    if !face_axes.visible {
+        return;
+    }
+    for normal in FACE_DIRECTIONS {
+        gizmos.arrow(
+            Vec3::ZERO,
+            normal * ARROW_LENGTH * settings.radius,
+            axis_color(normal),
+        );
+    }
+}
+
+/// Paints the name of each face normal next to its arrow tip.
+fn label_face_axes(
+    mut contexts: EguiContexts,
+    face_axes: Res<FaceAxes>,
+    settings: Res<PlanetSettings>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PanOrbitState>>,
+) {
+    if !face_axes.visible {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    let Ok((camera, camera_transform)) = q_camera.single() else {
+        return;
+    };
+    let painter = ctx.layer_painter(egui::LayerId::background());
+    for normal in FACE_DIRECTIONS {
+        let tip = normal * (ARROW_LENGTH + 0.1) * settings.radius;
+        let Ok(position) = camera.world_to_viewport(camera_transform, tip) else {
+            continue;
+        };
+        let [r, g, b, _] = axis_color(normal).to_u8_array();
+        painter.text(
+            egui::pos2(position.x, position.y),
+            egui::Align2::CENTER_CENTER,
+            axis_label(normal),
+            egui::FontId::proportional(16.0),
+            egui::Color32::from_rgb(r, g, b),
+        );
+    }
+}
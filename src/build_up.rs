@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{MIN_RESOLUTION, PlanetSettings};
+
+/// A resource to hold the "planet forming" intro that ramps the resolution up over time.
+#[derive(Resource, Debug)]
+pub struct ResolutionBuildUp {
+    pub start: u32,
+    pub end: u32,
+    /// How long the ramp takes, in seconds.
+    pub duration: f32,
+    /// The shortest time between regenerations, so high resolutions don't stall every frame.
+    pub step_interval: f32,
+    elapsed: f32,
+    since_step: f32,
+    active: bool,
+}
+
+impl Default for ResolutionBuildUp {
+    fn default() -> Self {
+        Self {
+            start: MIN_RESOLUTION,
+            end: 64,
+            duration: 3.0,
+            step_interval: 0.1,
+            elapsed: 0.0,
+            since_step: 0.0,
+            active: false,
+        }
+    }
+}
+
+impl ResolutionBuildUp {
+    /// Restarts the ramp from the start resolution.
+    pub fn play(&mut self) {
+        self.elapsed = 0.0;
+        self.since_step = f32::INFINITY;
+        self.active = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.active = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the resolution at the given progress through the ramp, from 0 to 1.
+    fn resolution_at(&self, t: f32) -> u32 {
+        let start = self.start.max(MIN_RESOLUTION) as f32;
+        let end = self.end.max(MIN_RESOLUTION) as f32;
+        (start + (end - start) * t).round() as u32
+    }
+}
+
+pub struct BuildUpPlugin;
+
+impl Plugin for BuildUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResolutionBuildUp>()
+            .add_systems(Update, animate_build_up);
+    }
+}
+
+/// Steps the planet resolution along an active ramp, at most once per step interval.
+fn animate_build_up(
+    time: Res<Time>,
+    mut build_up: ResMut<ResolutionBuildUp>,
+    mut settings: ResMut<PlanetSettings>,
+) {
+    if !build_up.active {
+        return;
+    }
+    let delta = time.delta_secs();
+    build_up.elapsed += delta;
+    build_up.since_step += delta;
+
+    let t = (build_up.elapsed / build_up.duration.max(f32::EPSILON)).min(1.0);
+    let resolution = build_up.resolution_at(t);
+    if t >= 1.0 {
+        // The final step always lands, however recently the previous one did.
+        build_up.active = false;
+    } else if build_up.since_step < build_up.step_interval {
+        return;
+    }
+    if settings.resolution != resolution {
+        settings.resolution = resolution;
+        build_up.since_step = 0.0;
+    }
+}
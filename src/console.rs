@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use bevy::log::tracing::{self, Level, field::Field, field::Visit};
+use bevy::log::tracing_subscriber::{Layer, layer::Context};
+use bevy::log::{BoxedLayer, LogPlugin};
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// How many log messages the console keeps before dropping the oldest.
+const MAX_ENTRIES: usize = 500;
+
+/// A log message captured for the console.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// A resource holding the receiving end of the log layer's channel.
+#[derive(Resource)]
+struct LogReceiver(Mutex<Receiver<LogEntry>>);
+
+/// A resource holding the recent log messages and the console's level filter.
+#[derive(Resource, Debug)]
+pub struct LogConsole {
+    pub entries: VecDeque<LogEntry>,
+    /// The most verbose level shown.
+    pub max_level: Level,
+}
+
+impl Default for LogConsole {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_level: Level::WARN,
+        }
+    }
+}
+
+/// Returns the log plugin with the console's capturing layer installed.
+pub fn log_plugin() -> LogPlugin {
+    LogPlugin {
+        custom_layer: console_layer,
+        ..default()
+    }
+}
+
+fn console_layer(app: &mut App) -> Option<BoxedLayer> {
+    let (sender, receiver) = mpsc::channel();
+    app.insert_resource(LogReceiver(Mutex::new(receiver)));
+    Some(Box::new(ConsoleLayer { sender }))
+}
+
+/// A tracing layer forwarding every event to the console.
+struct ConsoleLayer {
+    sender: Sender<LogEntry>,
+}
+
+impl<S: tracing::Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        // The receiver is gone once the app shuts down.
+        let _ = self.sender.send(LogEntry {
+            level: *event.metadata().level(),
+            target: visitor
+                .log_target
+                .unwrap_or_else(|| event.metadata().target().to_string()),
+            message: visitor.message,
+        });
+    }
+}
+
+/// Extracts the formatted message of a tracing event, and the original target of
+/// events forwarded from the `log` crate.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    log_target: Option<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "log.target" {
+            self.log_target = Some(value.to_string());
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LogConsole>()
+            .add_systems(Update, collect_log_entries)
+            .add_systems(EguiPrimaryContextPass, ui_console);
+    }
+}
+
+/// Moves newly captured messages into the console, dropping the oldest past the limit.
+fn collect_log_entries(receiver: Option<Res<LogReceiver>>, mut console: ResMut<LogConsole>) {
+    let Some(receiver) = receiver else { return };
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+    for entry in receiver.try_iter() {
+        console.entries.push_back(entry);
+    }
+    let excess = console.entries.len().saturating_sub(MAX_ENTRIES);
+    console.entries.drain(..excess);
+}
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::from_rgb(235, 90, 80),
+        Level::WARN => egui::Color32::from_rgb(230, 190, 60),
+        Level::INFO => egui::Color32::from_rgb(120, 190, 240),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+fn ui_console(mut contexts: EguiContexts, mut console: ResMut<LogConsole>) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Console")
+        .default_open(false)
+        .default_size([480.0, 240.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Show:");
+                egui::ComboBox::from_id_salt("console_level")
+                    .selected_text(console.max_level.as_str())
+                    .show_ui(ui, |ui| {
+                        for level in [
+                            Level::ERROR,
+                            Level::WARN,
+                            Level::INFO,
+                            Level::DEBUG,
+                            Level::TRACE,
+                        ] {
+                            ui.selectable_value(&mut console.max_level, level, level.as_str());
+                        }
+                    });
+                if ui.button("Clear").clicked() {
+                    console.entries.clear();
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .stick_to_bottom(true)
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    // Verbose levels compare greater, so this keeps the chosen level and more severe ones.
+                    for entry in console
+                        .entries
+                        .iter()
+                        .filter(|e| e.level <= console.max_level)
+                    {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.colored_label(level_color(entry.level), entry.level.as_str());
+                            ui.weak(&entry.target);
+                            ui.label(&entry.message);
+                        });
+                    }
+                });
+        });
+}
@@ -0,0 +1,223 @@
+use bevy::input::mouse::MouseButton;
+use bevy::prelude::*;
+use bevy::render::{
+    render_asset::RenderAssetUsages,
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_egui::EguiContexts;
+
+use crate::picking::PlanetPicker;
+use crate::{PlanetRoot, PlanetSettings, surface_point};
+
+/// How far decals float above the surface to avoid z-fighting with the planet.
+const DECAL_LIFT: f32 = 0.002;
+
+/// A quad anchored to the planet surface by where it was placed relative to the planet center.
+#[derive(Clone, Copy, Debug)]
+pub struct Decal {
+    pub direction: Vec3,
+    pub size: f32,
+}
+
+/// A resource holding all decals placed on the planet.
+#[derive(Resource, Default, Debug)]
+pub struct Decals {
+    pub entries: Vec<Decal>,
+}
+
+impl Decals {
+    pub fn add(&mut self, direction: Vec3, size: f32) {
+        self.entries.push(Decal { direction, size });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// A resource to hold the settings of the decal placement tool.
+#[derive(Resource, Debug)]
+pub struct DecalTool {
+    pub placing: bool,
+    pub size: f32,
+}
+
+impl Default for DecalTool {
+    fn default() -> Self {
+        Self {
+            placing: false,
+            size: 0.1,
+        }
+    }
+}
+
+/// A resource to hold the mesh and material shared by every decal.
+#[derive(Resource)]
+struct DecalAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// A component to identify a decal entity and the index of its entry in [`Decals`].
+#[derive(Component)]
+struct DecalMarker(usize);
+
+pub struct DecalPlugin;
+
+impl Plugin for DecalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Decals>()
+            .init_resource::<DecalTool>()
+            .add_systems(Startup, setup_decal_assets)
+            .add_systems(Update, (place_decal, sync_decals).chain());
+    }
+}
+
+fn setup_decal_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let texture = images.add(marker_image(64));
+    commands.insert_resource(DecalAssets {
+        mesh: meshes.add(Rectangle::new(1.0, 1.0)),
+        material: materials.add(StandardMaterial {
+            base_color_texture: Some(texture),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        }),
+    });
+}
+
+/// Generates a ring-and-dot marker texture with a transparent background.
+fn marker_image(size: u32) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let uv = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let r = uv.length();
+            let ring = (0.75..=0.95).contains(&r);
+            let dot = r <= 0.3;
+            if ring || dot {
+                data.extend_from_slice(&[230, 60, 40, 255]);
+            } else {
+                data.extend_from_slice(&[0, 0, 0, 0]);
+            }
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Places a decal where the cursor hits the planet when the placement tool is active.
+fn place_decal(
+    mut contexts: EguiContexts,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    tool: Res<DecalTool>,
+    mut decals: ResMut<Decals>,
+    mut picker: PlanetPicker,
+) {
+    if !tool.placing || !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_pointer_input()
+    {
+        return;
+    }
+    if let Some(point) = picker.cursor_point() {
+        decals.add(point, tool.size);
+    }
+}
+
+/// Respawns decals when they change and moves them onto the surface when the planet regenerates.
+fn sync_decals(
+    mut commands: Commands,
+    decals: Res<Decals>,
+    settings: Res<PlanetSettings>,
+    assets: Res<DecalAssets>,
+    q_root: Query<Entity, With<PlanetRoot>>,
+    mut q_decals: Query<(Entity, &DecalMarker, &mut Transform)>,
+) {
+    if decals.is_changed() {
+        for (entity, _, _) in &q_decals {
+            commands.entity(entity).despawn();
+        }
+        for (index, decal) in decals.entries.iter().enumerate() {
+            let mut entity = commands.spawn((
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.material.clone()),
+                decal_transform(decal, &settings),
+                DecalMarker(index),
+            ));
+            // Decals are placed in the planet's own space, so they turn with its spin.
+            if let Ok(root) = q_root.single() {
+                entity.insert(ChildOf(root));
+            }
+        }
+    } else if settings.is_changed() {
+        for (_, marker, mut transform) in &mut q_decals {
+            if let Some(decal) = decals.entries.get(marker.0) {
+                *transform = decal_transform(decal, &settings);
+            }
+        }
+    }
+}
+
+/// Orients a decal quad to the surface normal at its anchor point, relative to the planet root.
+fn decal_transform(decal: &Decal, settings: &PlanetSettings) -> Transform {
+    let (point, normal) = surface_point(decal.direction, settings);
+    Transform::from_translation(point + normal * DECAL_LIFT * settings.radius)
+        .with_rotation(Quat::from_rotation_arc(Vec3::Z, normal))
+        .with_scale(Vec3::splat(decal.size * settings.radius))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlanetSpin, spin_planet};
+
+    #[test]
+    fn decals_stay_on_their_spot_as_the_planet_spins() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), TransformPlugin))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Image>()
+            .init_resource::<PlanetSettings>()
+            .init_resource::<PlanetSpin>()
+            .init_resource::<Decals>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .add_systems(Startup, setup_decal_assets)
+            .add_systems(Update, (spin_planet, sync_decals).chain());
+        app.world_mut()
+            .spawn((PlanetRoot, Transform::default(), Visibility::default()));
+
+        let anchor = Vec3::new(0.3, 0.8, -0.5);
+        app.world_mut().resource_mut::<Decals>().add(anchor, 0.1);
+        let settings = PlanetSettings::default();
+        let (point, normal) = surface_point(anchor, &settings);
+        let spot = point + normal * DECAL_LIFT * settings.radius;
+        for angle in [1.0, 2.5] {
+            app.world_mut().resource_mut::<PlanetSpin>().angle = angle;
+            app.update();
+            let world = app.world_mut();
+            let mut decals = world.query_filtered::<&GlobalTransform, With<DecalMarker>>();
+            let decal = decals.single(world).expect("the decal was spawned");
+            let turned = Quat::from_rotation_y(angle) * spot;
+            assert!(decal.translation().abs_diff_eq(turned, 1e-4));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::f32::consts::PI;
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::inspect::{TriangleInspector, triangle_area};
+use crate::{BaseShape, PlanetFace, PlanetSettings};
+
+/// The size of the frame time graph in the FPS overlay.
+const FRAME_GRAPH_SIZE: egui::Vec2 = egui::vec2(120.0, 32.0);
+
+/// A resource holding the total area of the visible planet surface.
+#[derive(Resource, Debug)]
+pub struct SurfaceArea {
+    /// The area of the visible surface last generated, scaled to a unit radius, once the faces
+    /// have been generated.
+    pub total: Option<f32>,
+    /// Whether that surface is a round planet, whose area compares with the unit sphere's.
+    pub round: bool,
+    /// The radius the area is scaled to for display, e.g. in kilometers.
+    pub display_radius: f32,
+}
+
+impl Default for SurfaceArea {
+    fn default() -> Self {
+        Self {
+            total: None,
+            round: false,
+            display_radius: 6371.0,
+        }
+    }
+}
+
+/// A resource toggling the FPS and frame time overlay.
+#[derive(Resource, Default, Debug)]
+pub struct FrameStatsOverlay {
+    pub visible: bool,
+}
+
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.init_resource::<SurfaceArea>()
+            .init_resource::<FrameStatsOverlay>()
+            .add_systems(EguiPrimaryContextPass, (ui_diagnostics, ui_frame_stats));
+    }
+}
+
+impl SurfaceArea {
+    /// Records the area of the visible face meshes generated for the settings.
+    pub(crate) fn measure<'a>(
+        &mut self,
+        meshes: impl IntoIterator<Item = &'a Mesh>,
+        settings: &PlanetSettings,
+    ) {
+        // The meshes are generated at the planet radius; the area is measured on the unit planet.
+        let total: f32 = meshes.into_iter().map(mesh_area).sum();
+        self.total = Some(total / settings.radius.powi(2));
+        self.round =
+            settings.shape == BaseShape::Planet && settings.spherify && settings.flattening == 0.0;
+    }
+}
+
+fn mesh_area(mesh: &Mesh) -> f32 {
+    let (Some(VertexAttributeValues::Float32x3(positions)), Some(indices)) =
+        (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.indices())
+    else {
+        return 0.0;
+    };
+    let corners: Vec<usize> = indices.iter().collect();
+    corners
+        .chunks_exact(3)
+        .map(|t| {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[t[i]]));
+            triangle_area(a, b, c)
+        })
+        .sum()
+}
+
+/// A collapsible panel listing each planet face entity and the mesh it currently renders.
+fn ui_diagnostics(
+    mut contexts: EguiContexts,
+    meshes: Res<Assets<Mesh>>,
+    mut inspector: ResMut<TriangleInspector>,
+    mut area: ResMut<SurfaceArea>,
+    settings: Res<PlanetSettings>,
+    q_faces: Query<(Entity, &PlanetFace, &Mesh3d)>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Diagnostics")
+        .default_open(false)
+        .show(ctx, |ui| {
+            ui.label(format!("Mesh assets: {}", meshes.len()));
+            match area.total {
+                Some(total) => {
+                    if area.round {
+                        ui.label(format!(
+                            "Surface area: {total:.5} (unit sphere: {:.5})",
+                            4.0 * PI
+                        ));
+                    } else {
+                        ui.label(format!("Surface area: {total:.5} at unit radius"));
+                    }
+                    ui.horizontal(|ui| {
+                        let scaled = total * area.display_radius.powi(2);
+                        ui.label(format!("Scaled: {scaled:.4e} at radius"));
+                        ui.add(
+                            egui::DragValue::new(&mut area.display_radius)
+                                .range(0.001..=1.0e9)
+                                .speed(10.0),
+                        );
+                    });
+                }
+                None => {
+                    ui.label("Surface area: not yet generated");
+                }
+            }
+            egui::Grid::new("planet_faces")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Entity");
+                    ui.strong("Normal");
+                    ui.strong("Mesh");
+                    ui.strong("Vertices");
+                    ui.end_row();
+
+                    for (entity, face, mesh_3d) in &q_faces {
+                        ui.label(format!("{entity}"));
+                        ui.label(format!("{:+.0}", face.normal));
+                        ui.label(format!("{:?}", mesh_3d.id()));
+                        match meshes.get(mesh_3d) {
+                            Some(mesh) => ui.label(mesh.count_vertices().to_string()),
+                            None => ui.label("render only"),
+                        };
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+
+            let changed = settings.non_default_fields();
+            egui::CollapsingHeader::new(format!("Non-default settings ({})", changed.len()))
+                .id_salt("non_default_settings")
+                .show(ui, |ui| {
+                    if changed.is_empty() {
+                        ui.label("All settings are at their defaults.");
+                    }
+                    egui::Grid::new("settings_diff")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (field, value) in changed {
+                                ui.monospace(field);
+                                ui.add(egui::Label::new(value).wrap());
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+
+            ui.checkbox(&mut inspector.enabled, "Inspect triangle under cursor");
+            ui.add_enabled(
+                inspector.enabled,
+                egui::Checkbox::new(&mut inspector.show_one_ring, "Show one-ring neighborhood"),
+            );
+            match &inspector.picked {
+                Some(picked) => {
+                    ui.label(format!(
+                        "Entity {}, triangle {}",
+                        picked.entity, picked.triangle_index
+                    ));
+                    ui.label(format!(
+                        "Vertex indices: {} {} {}",
+                        picked.indices[0], picked.indices[1], picked.indices[2]
+                    ));
+                    ui.label(format!("Area: {:.6}", picked.area()));
+                    if inspector.show_one_ring {
+                        ui.label(format!("One-ring: {} triangles", picked.one_ring.len()));
+                    }
+                }
+                None if inspector.enabled => {
+                    ui.label("No triangle under cursor.");
+                }
+                None => {}
+            }
+        });
+}
+
+/// Shows the smoothed FPS and frame time in the top right corner, over a graph of the recent
+/// frame times.
+fn ui_frame_stats(
+    mut contexts: EguiContexts,
+    overlay: Res<FrameStatsOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+) {
+    if !overlay.visible {
+        return;
+    }
+    let Some(frame_time) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) else {
+        return;
+    };
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed());
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Area::new(egui::Id::new("frame_stats"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.monospace(format!("{:.0} FPS", fps.unwrap_or(0.0)));
+                ui.monospace(format!("{:.2} ms", frame_time.smoothed().unwrap_or(0.0)));
+                let times: Vec<f64> = frame_time.values().copied().collect();
+                frame_time_graph(ui, &times);
+            });
+        });
+}
+
+/// Draws the frame times as a line, oldest on the left, scaled to the slowest of them.
+fn frame_time_graph(ui: &mut egui::Ui, times: &[f64]) {
+    let (rect, _) = ui.allocate_exact_size(FRAME_GRAPH_SIZE, egui::Sense::hover());
+    let slowest = times.iter().copied().fold(f64::EPSILON, f64::max);
+    let step = rect.width() / times.len().saturating_sub(1).max(1) as f32;
+    let points = times
+        .iter()
+        .enumerate()
+        .map(|(i, &time)| {
+            let height = (time / slowest) as f32 * rect.height();
+            egui::pos2(rect.left() + i as f32 * step, rect.bottom() - height)
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.0, ui.visuals().text_color()),
+    ));
+}
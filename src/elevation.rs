@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence;
+
+/// A color pinned to an elevation in the terrain gradient.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// The height above the unit radius, relative to the planet radius.
+    pub elevation: f32,
+    #[serde(with = "persistence::srgba")]
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(elevation: f32, color: Color) -> Self {
+        Self { elevation, color }
+    }
+}
+
+/// The settings of coloring the terrain by its elevation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElevationColors {
+    pub enabled: bool,
+    /// The gradient the elevation is mapped through; below the lowest stop and above the
+    /// highest, their colors continue.
+    pub stops: Vec<GradientStop>,
+}
+
+impl Default for ElevationColors {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stops: vec![
+                GradientStop::new(-0.04, Color::srgb(0.02, 0.08, 0.3)),
+                GradientStop::new(-0.005, Color::srgb(0.1, 0.35, 0.6)),
+                GradientStop::new(0.0, Color::srgb(0.8, 0.75, 0.5)),
+                GradientStop::new(0.01, Color::srgb(0.2, 0.5, 0.15)),
+                GradientStop::new(0.04, Color::srgb(0.4, 0.3, 0.2)),
+                GradientStop::new(0.07, Color::srgb(0.95, 0.95, 0.97)),
+            ],
+        }
+    }
+}
+
+impl ElevationColors {
+    /// Orders the stops from the lowest elevation up.
+    pub fn sort_stops(&mut self) {
+        self.stops
+            .sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+    }
+
+    /// Returns the gradient's color at the given elevation, blended linearly between stops.
+    pub fn color_at(&self, elevation: f32) -> LinearRgba {
+        gradient_color(&sorted_stops(&self.stops), elevation)
+    }
+
+    /// Returns the lowest and highest elevation of the stops, if there are any.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        let elevations = self.stops.iter().map(|stop| stop.elevation);
+        let low = elevations.clone().reduce(f32::min)?;
+        let high = elevations.reduce(f32::max)?;
+        Some((low, high))
+    }
+}
+
+/// Returns the stops ordered from the lowest elevation up, whatever order they are listed in.
+fn sorted_stops(stops: &[GradientStop]) -> Vec<&GradientStop> {
+    let mut sorted: Vec<&GradientStop> = stops.iter().collect();
+    sorted.sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+    sorted
+}
+
+/// Blends between the two stops around `elevation`; `stops` must be sorted by elevation.
+fn gradient_color(stops: &[&GradientStop], elevation: f32) -> LinearRgba {
+    let Some(upper) = stops.iter().position(|stop| stop.elevation > elevation) else {
+        return stops
+            .last()
+            .map_or(LinearRgba::WHITE, |stop| stop.color.into());
+    };
+    if upper == 0 {
+        return stops[0].color.into();
+    }
+    let (low, high) = (stops[upper - 1], stops[upper]);
+    let t = (elevation - low.elevation) / (high.elevation - low.elevation);
+    LinearRgba::from(low.color).mix(&high.color.into(), t)
+}
+
+/// Colors the mesh's vertices by the elevation of the surface in their direction.
+pub fn apply_elevation_colors(
+    mesh: &mut Mesh,
+    colors: &ElevationColors,
+    elevation: impl Fn(Vec3) -> f32,
+) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let stops = sorted_stops(&colors.stops);
+    let vertex_colors: Vec<[f32; 4]> = positions
+        .iter()
+        .map(|&position| {
+            let direction = Vec3::from(position).normalize_or_zero();
+            gradient_color(&stops, elevation(direction)).to_f32_array()
+        })
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+}
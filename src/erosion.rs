@@ -0,0 +1,334 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::PlanetSettings;
+use crate::generation::{FACE_DIRECTIONS, face_axes, face_for_direction};
+use crate::noise::NoiseLayer;
+
+/// How many cells span each cube face of the grid the droplets run over.
+const MAP_RESOLUTION: usize = 96;
+
+/// How much of its previous direction a droplet keeps each step, against the slope's pull.
+const INERTIA: f32 = 0.05;
+
+/// How much sediment a droplet carries per unit of speed, water and drop.
+const SEDIMENT_CAPACITY: f32 = 4.0;
+
+/// The least sediment a droplet can carry, so it keeps carving gentle slopes.
+const MIN_SEDIMENT_CAPACITY: f32 = 1e-4;
+
+/// The fraction of the sediment above capacity dropped each step.
+const DEPOSITION: f32 = 0.3;
+
+/// The fraction of its water a droplet loses each step.
+const EVAPORATION: f32 = 0.02;
+
+const GRAVITY: f32 = 4.0;
+
+/// How many steps a droplet runs before it is dropped.
+const DROPLET_LIFETIME: u32 = 40;
+
+/// The settings of the hydraulic erosion carving the noise terrain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Erosion {
+    pub enabled: bool,
+    /// How many raindrops run downhill over the planet, each wearing away and depositing soil.
+    pub droplets: u32,
+    /// How much of the soil it could carry a droplet picks up each step, from 0 to 1.
+    pub strength: f32,
+}
+
+impl Default for Erosion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            droplets: 20_000,
+            strength: 0.3,
+        }
+    }
+}
+
+/// What an erosion map was simulated from.
+#[derive(PartialEq)]
+struct ErosionKey {
+    seed: u32,
+    variation: f32,
+    layers: Vec<NoiseLayer>,
+    erosion: Erosion,
+}
+
+/// The last simulated map, shared by the faces generated in parallel and by surface queries.
+static CACHE: Mutex<Option<(ErosionKey, Arc<ErosionMap>)>> = Mutex::new(None);
+
+/// The change erosion makes to the noise height, over a grid on the six cube faces.
+#[derive(Debug)]
+pub(crate) struct ErosionMap {
+    /// The height change of each cell, relative to the planet radius; face by face, row by row.
+    change: Vec<f32>,
+}
+
+impl ErosionMap {
+    /// Returns the map for the settings' noise and erosion, simulating it only when they
+    /// differ from the last call's.
+    pub(crate) fn cached(settings: &PlanetSettings) -> Arc<Self> {
+        let key = ErosionKey {
+            seed: settings.seed,
+            variation: settings.variation,
+            layers: settings.audible_noise_layers().cloned().collect(),
+            erosion: settings.erosion.clone(),
+        };
+        // Holding the lock while simulating makes faces generated in parallel wait for one map.
+        let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((cached_key, map)) = cache.as_ref()
+            && *cached_key == key
+        {
+            return map.clone();
+        }
+        let map = Arc::new(Self::simulate(settings));
+        *cache = Some((key, map.clone()));
+        map
+    }
+
+    /// Runs the settings' droplets over the noise terrain, from starting points drawn from the
+    /// seed, and records how far each cell was lowered or raised.
+    fn simulate(settings: &PlanetSettings) -> Self {
+        let offset = settings.noise_offset();
+        let cells_per_face = MAP_RESOLUTION * MAP_RESOLUTION;
+        let heights: Vec<f32> = (0..FACE_DIRECTIONS.len() * cells_per_face)
+            .map(|index| {
+                let (face, cell) = (index / cells_per_face, index % cells_per_face);
+                let cell = Vec2::new(
+                    (cell % MAP_RESOLUTION) as f32,
+                    (cell / MAP_RESOLUTION) as f32,
+                );
+                settings.layered_noise_height(cube_point(face, cell).normalize(), offset)
+            })
+            .collect();
+        let mut eroded = heights.clone();
+        let mut random = Pcg(settings.seed);
+        let step = 2.0 / MAP_RESOLUTION as f32;
+        let strength = settings.erosion.strength.clamp(0.0, 1.0);
+
+        for _ in 0..settings.erosion.droplets {
+            let mut direction = random.direction();
+            let mut flow = Vec3::ZERO;
+            let (mut speed, mut water, mut sediment) = (1.0, 1.0, 0.0);
+            for _ in 0..DROPLET_LIFETIME {
+                let (face, cell) = grid_point(direction);
+                let normal = FACE_DIRECTIONS[face];
+                let (axis_a, axis_b) = face_axes(normal);
+                let cell_corners = corners(face, cell);
+                let (height, gradient) = sample(&eroded, &cell_corners, cell);
+                // Flow along the surface of the face the droplet is on.
+                flow = (flow * INERTIA
+                    - (gradient.x * axis_a + gradient.y * axis_b) * (1.0 - INERTIA))
+                    .reject_from_normalized(normal);
+                let Some(unit_flow) = flow.try_normalize() else {
+                    break;
+                };
+                flow = unit_flow;
+                let next = (cube_point(face, cell) + flow * step).normalize();
+                let (next_face, next_cell) = grid_point(next);
+                let next_height = sample(&eroded, &corners(next_face, next_cell), next_cell).0;
+                let drop = height - next_height;
+
+                let capacity =
+                    (drop * speed * water * SEDIMENT_CAPACITY).max(MIN_SEDIMENT_CAPACITY);
+                if sediment > capacity || drop < 0.0 {
+                    // Uphill, fill the pit behind; otherwise drop the excess.
+                    let amount = if drop < 0.0 {
+                        (-drop).min(sediment)
+                    } else {
+                        (sediment - capacity) * DEPOSITION
+                    };
+                    sediment -= amount;
+                    for (index, weight) in cell_corners {
+                        eroded[index] += amount * weight;
+                    }
+                } else {
+                    // Never dig deeper than the drop, which would leave a pit.
+                    let amount = ((capacity - sediment) * strength).min(drop);
+                    sediment += amount;
+                    for (index, weight) in cell_corners {
+                        eroded[index] -= amount * weight;
+                    }
+                }
+                speed = (speed * speed + drop * GRAVITY).max(0.0).sqrt();
+                water *= 1.0 - EVAPORATION;
+                direction = next;
+            }
+        }
+
+        Self {
+            change: eroded.iter().zip(&heights).map(|(e, h)| e - h).collect(),
+        }
+    }
+
+    /// Returns the height change at the given unit direction, interpolated between cells.
+    pub(crate) fn height_change(&self, direction: Vec3) -> f32 {
+        let (face, cell) = grid_point(direction);
+        corners(face, cell)
+            .iter()
+            .map(|&(index, weight)| self.change[index] * weight)
+            .sum()
+    }
+}
+
+/// Returns the index of the cube face a direction maps onto and its position there, in cells.
+fn grid_point(direction: Vec3) -> (usize, Vec2) {
+    let normal = face_for_direction(direction);
+    let face = FACE_DIRECTIONS
+        .iter()
+        .position(|&face_normal| face_normal == normal)
+        .unwrap_or_default();
+    let (axis_a, axis_b) = face_axes(normal);
+    let on_cube = direction / direction.dot(normal);
+    let uv = Vec2::new(on_cube.dot(axis_a), on_cube.dot(axis_b));
+    (face, (uv + 1.0) * 0.5 * MAP_RESOLUTION as f32 - 0.5)
+}
+
+/// Returns the point on the unit cube at a position on a face, in cells.
+fn cube_point(face: usize, cell: Vec2) -> Vec3 {
+    let normal = FACE_DIRECTIONS[face];
+    let (axis_a, axis_b) = face_axes(normal);
+    let uv = (cell + 0.5) / MAP_RESOLUTION as f32 * 2.0 - 1.0;
+    normal + uv.x * axis_a + uv.y * axis_b
+}
+
+/// Returns the index of a cell; cells past the edge of a face are taken from the face beyond.
+fn cell_index(face: usize, x: i32, y: i32) -> usize {
+    let last = MAP_RESOLUTION as i32 - 1;
+    let (face, x, y) = if (0..=last).contains(&x) && (0..=last).contains(&y) {
+        (face, x, y)
+    } else {
+        let cell = Vec2::new(x as f32, y as f32);
+        let (face, cell) = grid_point(cube_point(face, cell).normalize());
+        let cell = cell
+            .round()
+            .as_ivec2()
+            .clamp(IVec2::ZERO, IVec2::splat(last));
+        (face, cell.x, cell.y)
+    };
+    (face * MAP_RESOLUTION + y as usize) * MAP_RESOLUTION + x as usize
+}
+
+/// Returns the four cells around a position on a face and their bilinear weights.
+fn corners(face: usize, cell: Vec2) -> [(usize, f32); 4] {
+    let base = cell.floor();
+    let f = cell - base;
+    let (x, y) = (base.x as i32, base.y as i32);
+    [
+        (cell_index(face, x, y), (1.0 - f.x) * (1.0 - f.y)),
+        (cell_index(face, x + 1, y), f.x * (1.0 - f.y)),
+        (cell_index(face, x, y + 1), (1.0 - f.x) * f.y),
+        (cell_index(face, x + 1, y + 1), f.x * f.y),
+    ]
+}
+
+/// Returns the interpolated height between four corners and its slope per cell.
+fn sample(heights: &[f32], corners: &[(usize, f32); 4], cell: Vec2) -> (f32, Vec2) {
+    let [h00, h10, h01, h11] = corners.map(|(index, _)| heights[index]);
+    let f = cell - cell.floor();
+    let height = corners
+        .iter()
+        .map(|&(index, weight)| heights[index] * weight)
+        .sum();
+    let gradient = Vec2::new(
+        (h10 - h00) * (1.0 - f.y) + (h11 - h01) * f.y,
+        (h01 - h00) * (1.0 - f.x) + (h11 - h10) * f.x,
+    );
+    (height, gradient)
+}
+
+/// A PCG random number generator, so the droplets fall in the same places for a seed.
+struct Pcg(u32);
+
+impl Pcg {
+    /// Returns a value in `[0, 1)`.
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(747_796_405).wrapping_add(2_891_336_453);
+        let word = ((self.0 >> ((self.0 >> 28) + 4)) ^ self.0).wrapping_mul(277_803_737);
+        (((word >> 22) ^ word) >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Returns a unit vector uniformly distributed over the sphere.
+    fn direction(&mut self) -> Vec3 {
+        let y = self.next() * 2.0 - 1.0;
+        let angle = self.next() * std::f32::consts::TAU;
+        let ring = (1.0 - y * y).max(0.0).sqrt();
+        Vec3::new(ring * angle.cos(), y, ring * angle.sin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eroded_settings(seed: u32) -> PlanetSettings {
+        PlanetSettings {
+            seed,
+            noise_layers: vec![NoiseLayer {
+                amplitude: 0.1,
+                ..default()
+            }],
+            erosion: Erosion {
+                enabled: true,
+                droplets: 2_000,
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    #[test]
+    fn erosion_is_reproducible_from_the_seed() {
+        let first = ErosionMap::simulate(&eroded_settings(7));
+        let again = ErosionMap::simulate(&eroded_settings(7));
+        let other = ErosionMap::simulate(&eroded_settings(8));
+        assert_eq!(first.change, again.change);
+        assert_ne!(first.change, other.change);
+    }
+
+    #[test]
+    fn erosion_carves_and_deposits_without_adding_soil() {
+        let map = ErosionMap::simulate(&eroded_settings(3));
+        assert!(map.change.iter().any(|&change| change < 0.0));
+        assert!(map.change.iter().any(|&change| change > 0.0));
+        // Sediment still carried by evaporated droplets is lost, never created.
+        assert!(map.change.iter().sum::<f32>() <= 1e-4);
+    }
+
+    #[test]
+    fn erosion_changes_the_surface_height() {
+        let eroded = eroded_settings(3);
+        let plain = PlanetSettings {
+            erosion: Erosion::default(),
+            ..eroded.clone()
+        };
+        let (eroded_height, plain_height) = (eroded.noise_height_fn(), plain.noise_height_fn());
+        let differs = (0..200).any(|i| {
+            let direction = Vec3::new((i as f32).sin(), (i as f32 * 0.7).cos(), 0.3).normalize();
+            eroded_height(direction) != plain_height(direction)
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn cells_past_a_face_edge_come_from_the_neighboring_face() {
+        let last = MAP_RESOLUTION as i32 - 1;
+        for face in 0..FACE_DIRECTIONS.len() {
+            for (x, y) in [
+                (-1, 10),
+                (MAP_RESOLUTION as i32, 10),
+                (10, -1),
+                (10, last + 1),
+            ] {
+                let index = cell_index(face, x, y);
+                assert_ne!(index / (MAP_RESOLUTION * MAP_RESOLUTION), face);
+            }
+        }
+    }
+}
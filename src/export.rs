@@ -0,0 +1,1095 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+
+use crate::generation::{MeshGenError, weld_groups};
+use crate::texture::PlanetSurfaceMaterial;
+use crate::{
+    FACE_DIRECTIONS, PanOrbitState, PlanetMaterial, PlanetSettings, PlanetSpin, face_visibility,
+    surface_mesh,
+};
+
+/// The file written by the SVG wireframe export.
+pub const SVG_PATH: &str = "planet_wireframe.svg";
+
+/// The file written by the OBJ export.
+pub const OBJ_PATH: &str = "planet.obj";
+
+/// The file written by the PLY export.
+pub const PLY_PATH: &str = "planet.ply";
+
+/// The file written by the binary glTF export.
+pub const GLB_PATH: &str = "planet.glb";
+
+/// The file written by the STL export.
+pub const STL_PATH: &str = "planet.stl";
+
+/// The file written by the welded OBJ export.
+pub const WELDED_OBJ_PATH: &str = "planet_welded.obj";
+
+/// The file written by the USD export.
+pub const USDA_PATH: &str = "planet.usda";
+
+/// Positions closer than this, relative to the farthest vertex from the center, are merged into
+/// one vertex when welding face seams.
+const WELD_TOLERANCE: f32 = 1e-5;
+
+/// An event asking for the planet to be exported.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRequest {
+    Svg,
+    Obj,
+    Ply,
+    Glb,
+    Stl,
+    /// Writes the planet at each LOD resolution to its own OBJ file.
+    LodChain,
+    Usda,
+    /// Writes one welded mesh with a group and material slot per face.
+    WeldedObj,
+}
+
+impl ExportRequest {
+    /// The single-file formats, in the order the export dropdown lists them.
+    pub const FORMATS: [ExportRequest; 7] = [
+        ExportRequest::Obj,
+        ExportRequest::WeldedObj,
+        ExportRequest::Ply,
+        ExportRequest::Glb,
+        ExportRequest::Stl,
+        ExportRequest::Usda,
+        ExportRequest::Svg,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportRequest::Svg => "SVG Wireframe",
+            ExportRequest::Obj => "OBJ",
+            ExportRequest::Ply => "PLY",
+            ExportRequest::Glb => "GLB",
+            ExportRequest::Stl => "STL",
+            ExportRequest::LodChain => "LOD Chain",
+            ExportRequest::Usda => "USDA",
+            ExportRequest::WeldedObj => "Welded OBJ",
+        }
+    }
+}
+
+/// A resource to hold the export options chosen in the UI.
+#[derive(Resource, Debug)]
+pub struct ExportSettings {
+    /// The format the Export button writes.
+    pub format: ExportRequest,
+    pub svg_hidden_line_removal: bool,
+    /// The resolution of each exported LOD, from LOD0 down.
+    pub lod_resolutions: Vec<u32>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            format: ExportRequest::Obj,
+            svg_hidden_line_removal: true,
+            lod_resolutions: vec![64, 32, 16],
+        }
+    }
+}
+
+/// Returns the file the LOD at `level` is written to.
+pub fn lod_path(level: usize) -> String {
+    format!("planet_lod{level}.obj")
+}
+
+/// A camera view used to project mesh edges into a 2D drawing.
+pub struct SvgView {
+    /// Maps mesh-space positions to clip space.
+    pub clip_from_model: Mat4,
+    /// The camera position in mesh space, used to cull back-facing triangles.
+    pub eye: Vec3,
+    /// The drawing size in pixels.
+    pub size: Vec2,
+}
+
+impl SvgView {
+    /// Returns the view of meshes placed by `world_from_model` seen through a camera, with the
+    /// camera's transform and its `clip_from_view` projection.
+    pub fn new(
+        clip_from_view: Mat4,
+        camera_transform: &GlobalTransform,
+        world_from_model: Mat4,
+        size: Vec2,
+    ) -> Self {
+        let model_from_world = world_from_model.inverse();
+        Self {
+            clip_from_model: clip_from_view
+                * camera_transform.compute_matrix().inverse()
+                * world_from_model,
+            eye: model_from_world.transform_point3(camera_transform.translation()),
+            size,
+        }
+    }
+}
+
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ExportRequest>()
+            .init_resource::<ExportSettings>()
+            .add_systems(Update, handle_export_requests);
+    }
+}
+
+fn handle_export_requests(
+    mut requests: EventReader<ExportRequest>,
+    export_settings: Res<ExportSettings>,
+    settings: Res<PlanetSettings>,
+    planet_material: Res<PlanetMaterial>,
+    materials: Res<Assets<PlanetSurfaceMaterial>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<PanOrbitState>>,
+    spin: Res<PlanetSpin>,
+) {
+    for request in requests.read() {
+        // The face mesh assets may have no CPU copy, so export regenerates them.
+        let faces = match visible_face_meshes(&settings) {
+            Ok(faces) => faces,
+            Err(err) => {
+                error!("Export failed: {err}");
+                continue;
+            }
+        };
+        let face_meshes: Vec<&Mesh> = faces.iter().collect();
+        let result = match request {
+            ExportRequest::Svg => {
+                let Ok((camera, camera_transform)) = q_camera.single() else {
+                    continue;
+                };
+                let Some(size) = camera.logical_viewport_size() else {
+                    continue;
+                };
+                // The faces turn with the spin, so the drawing does too.
+                let view = SvgView::new(
+                    camera.clip_from_view(),
+                    camera_transform,
+                    Mat4::from_rotation_y(spin.angle),
+                    size,
+                );
+                export_svg(
+                    &face_meshes,
+                    &view,
+                    export_settings.svg_hidden_line_removal,
+                    Path::new(SVG_PATH),
+                )
+                .map(|()| SVG_PATH.to_string())
+            }
+            ExportRequest::Obj => {
+                export_obj(&face_meshes, Path::new(OBJ_PATH)).map(|()| OBJ_PATH.to_string())
+            }
+            ExportRequest::Ply => export_ply(&face_meshes, settings.color, Path::new(PLY_PATH))
+                .map(|()| PLY_PATH.to_string()),
+            ExportRequest::Glb => {
+                let Some(material) = materials.get(&planet_material.0) else {
+                    continue;
+                };
+                export_glb(&face_meshes, &material.base, Path::new(GLB_PATH))
+                    .map(|()| GLB_PATH.to_string())
+            }
+            ExportRequest::Stl => {
+                export_stl(&face_meshes, Path::new(STL_PATH)).map(|()| STL_PATH.to_string())
+            }
+            ExportRequest::Usda => export_usda(&face_meshes, settings.color, Path::new(USDA_PATH))
+                .map(|()| USDA_PATH.to_string()),
+            ExportRequest::WeldedObj => export_welded_obj(&face_meshes, Path::new(WELDED_OBJ_PATH))
+                .map(|()| WELDED_OBJ_PATH.to_string()),
+            ExportRequest::LodChain => {
+                export_lod_chain(&settings, &export_settings.lod_resolutions)
+                    .map(|()| format!("{} LOD files", export_settings.lod_resolutions.len()))
+            }
+        };
+        match result {
+            Ok(path) => info!("Exported planet to {path}"),
+            Err(err) => error!("Export failed: {err}"),
+        }
+    }
+}
+
+/// Regenerates the visible faces at each resolution and writes every LOD as a welded OBJ mesh,
+/// warning about any LOD that is not watertight.
+fn export_lod_chain(settings: &PlanetSettings, resolutions: &[u32]) -> io::Result<()> {
+    for (level, &resolution) in resolutions.iter().enumerate() {
+        let lod_settings = PlanetSettings {
+            resolution,
+            ..settings.clone()
+        };
+        let faces = visible_face_meshes(&lod_settings).map_err(io::Error::other)?;
+        let (positions, triangles) = weld(&faces.iter().collect::<Vec<_>>());
+        let path = lod_path(level);
+        write_obj(&positions, &triangles, Path::new(&path))?;
+
+        let open_edges = open_edge_count(&triangles);
+        if open_edges == 0 {
+            info!(
+                "LOD{level} at resolution {resolution}: {} triangles, watertight",
+                triangles.len()
+            );
+        } else {
+            warn!("LOD{level} at resolution {resolution} has {open_edges} open edges");
+        }
+    }
+    Ok(())
+}
+
+/// Generates the mesh of each face that is shown for the given settings.
+fn visible_face_meshes(settings: &PlanetSettings) -> Result<Vec<Mesh>, MeshGenError> {
+    FACE_DIRECTIONS
+        .into_iter()
+        .filter(|&normal| face_visibility(settings, normal) != Visibility::Hidden)
+        .map(|normal| surface_mesh(settings, normal))
+        .collect()
+}
+
+/// Writes the meshes as one Wavefront OBJ object, with normals and, where the meshes have them,
+/// texture coordinates and sRGB vertex colors in the common `v x y z r g b` extension.
+///
+/// Vertices are not welded, so every face keeps its own normals and UVs along the seams.
+pub fn export_obj(meshes: &[&Mesh], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "o Planet")?;
+    // OBJ indices are 1-based and count every vertex written before, across all meshes.
+    let mut vertex_offset = 1;
+    let mut normal_offset = 1;
+    let mut uv_offset = 1;
+    for mesh in meshes {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            warn!("Skipping a non-triangle-list mesh in the OBJ export");
+            continue;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let Some(Indices::U32(indices)) = mesh.indices() else {
+            continue;
+        };
+        let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+            _ => None,
+        };
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs),
+            _ => None,
+        };
+        let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => Some(colors),
+            _ => None,
+        };
+
+        for (index, [x, y, z]) in positions.iter().enumerate() {
+            match colors.and_then(|colors| colors.get(index)) {
+                Some(&color) => {
+                    let [r, g, b, _] =
+                        Srgba::from(LinearRgba::from_f32_array(color)).to_f32_array();
+                    writeln!(out, "v {x} {y} {z} {r} {g} {b}")?;
+                }
+                None => writeln!(out, "v {x} {y} {z}")?,
+            }
+        }
+        for [x, y, z] in normals.into_iter().flatten() {
+            writeln!(out, "vn {x} {y} {z}")?;
+        }
+        for [u, v] in uvs.into_iter().flatten() {
+            // OBJ puts the V origin at the bottom of the image, Bevy at the top.
+            writeln!(out, "vt {u} {}", 1.0 - v)?;
+        }
+
+        for triangle in indices.chunks_exact(3) {
+            write!(out, "f")?;
+            for &i in triangle {
+                let (vertex, normal, uv) = (vertex_offset + i, normal_offset + i, uv_offset + i);
+                match (uvs.is_some(), normals.is_some()) {
+                    (true, true) => write!(out, " {vertex}/{uv}/{normal}")?,
+                    (true, false) => write!(out, " {vertex}/{uv}")?,
+                    (false, true) => write!(out, " {vertex}//{normal}")?,
+                    (false, false) => write!(out, " {vertex}")?,
+                }
+            }
+            writeln!(out)?;
+        }
+
+        vertex_offset += positions.len() as u32;
+        if normals.is_some() {
+            normal_offset += positions.len() as u32;
+        }
+        if uvs.is_some() {
+            uv_offset += positions.len() as u32;
+        }
+    }
+    out.flush()
+}
+
+/// Writes the meshes as one mesh in a binary little-endian PLY file, with normals and 8-bit sRGB
+/// vertex colors; vertices of meshes without colors take `color`.
+///
+/// Like the OBJ export, the faces are concatenated rather than welded.
+pub fn export_ply(meshes: &[&Mesh], color: Color, path: &Path) -> io::Result<()> {
+    let fallback = color.to_linear().to_f32_array();
+    let mut vertices: Vec<([f32; 3], [f32; 3], [f32; 4])> = Vec::new();
+    let mut triangles: Vec<[u32; 3]> = Vec::new();
+    for mesh in meshes {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            warn!("Skipping a non-triangle-list mesh in the PLY export");
+            continue;
+        }
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let Some(Indices::U32(indices)) = mesh.indices() else {
+            continue;
+        };
+        let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+            _ => None,
+        };
+        let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => Some(colors),
+            _ => None,
+        };
+        let offset = vertices.len() as u32;
+        for (index, &position) in positions.iter().enumerate() {
+            let normal = normals.and_then(|normals| normals.get(index)).copied();
+            let color = colors.and_then(|colors| colors.get(index)).copied();
+            vertices.push((
+                position,
+                normal.unwrap_or_default(),
+                color.unwrap_or(fallback),
+            ));
+        }
+        triangles.extend(
+            indices
+                .chunks_exact(3)
+                .map(|triangle| [triangle[0], triangle[1], triangle[2]].map(|i| offset + i)),
+        );
+    }
+
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "ply")?;
+    writeln!(out, "format binary_little_endian 1.0")?;
+    writeln!(out, "comment bevy_mesh planet")?;
+    writeln!(out, "element vertex {}", vertices.len())?;
+    for property in ["x", "y", "z", "nx", "ny", "nz"] {
+        writeln!(out, "property float {property}")?;
+    }
+    for property in ["red", "green", "blue"] {
+        writeln!(out, "property uchar {property}")?;
+    }
+    writeln!(out, "element face {}", triangles.len())?;
+    writeln!(out, "property list uchar uint vertex_indices")?;
+    writeln!(out, "end_header")?;
+    for (position, normal, color) in &vertices {
+        for value in position.iter().chain(normal) {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        out.write_all(&Srgba::from(LinearRgba::from_f32_array(*color)).to_u8_array_no_alpha())?;
+    }
+    for triangle in &triangles {
+        out.write_all(&[3])?;
+        for index in triangle {
+            out.write_all(&index.to_le_bytes())?;
+        }
+    }
+    out.flush()
+}
+
+/// Writes the meshes as one mesh in a binary glTF (`.glb`) file, with positions, normals, UVs
+/// and a metallic-roughness material taking its factors from `material`.
+///
+/// Like the OBJ export, the faces are concatenated rather than welded.
+pub fn export_glb(meshes: &[&Mesh], material: &StandardMaterial, path: &Path) -> io::Result<()> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    for mesh in meshes {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            warn!("Skipping a non-triangle-list mesh in the glTF export");
+            continue;
+        }
+        let Some(VertexAttributeValues::Float32x3(mesh_positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let Some(Indices::U32(mesh_indices)) = mesh.indices() else {
+            continue;
+        };
+        // Every vertex needs every attribute, so missing ones are filled with zeros.
+        let count = mesh_positions.len();
+        match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(values)) => normals.extend(values),
+            _ => normals.extend(std::iter::repeat_n([0.0; 3], count)),
+        }
+        match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(values)) => uvs.extend(values),
+            _ => uvs.extend(std::iter::repeat_n([0.0; 2], count)),
+        }
+        let offset = positions.len() as u32;
+        indices.extend(mesh_indices.iter().map(|&i| offset + i));
+        positions.extend(mesh_positions);
+    }
+    if positions.is_empty() {
+        return Err(io::Error::other("no vertices to export"));
+    }
+
+    let (min, max) = positions
+        .iter()
+        .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), &p| {
+            (min.min(p.into()), max.max(p.into()))
+        });
+    let mut bin = Vec::new();
+    let floats = positions
+        .iter()
+        .chain(&normals)
+        .flatten()
+        .chain(uvs.iter().flatten());
+    for value in floats {
+        bin.extend(value.to_le_bytes());
+    }
+    for index in &indices {
+        bin.extend(index.to_le_bytes());
+    }
+    let vertex_count = positions.len();
+    let positions_length = vertex_count * 12;
+    let uvs_offset = positions_length * 2;
+    let indices_offset = uvs_offset + vertex_count * 8;
+
+    let color = material.base_color.to_linear();
+    let json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"bevy_mesh"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0,"name":"Planet"}}],"#,
+            r#""meshes":[{{"name":"Planet","primitives":[{{"#,
+            r#""attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2}},"indices":3,"material":0}}]}}],"#,
+            r#""materials":[{{"name":"Planet","doubleSided":{double_sided},"#,
+            r#""pbrMetallicRoughness":{{"baseColorFactor":[{r},{g},{b},{a}],"#,
+            r#""metallicFactor":{metallic},"roughnessFactor":{roughness}}}}}],"#,
+            r#""buffers":[{{"byteLength":{buffer_length}}}],"#,
+            r#""bufferViews":["#,
+            r#"{{"buffer":0,"byteOffset":0,"byteLength":{positions_length},"target":34962}},"#,
+            r#"{{"buffer":0,"byteOffset":{positions_length},"byteLength":{positions_length},"target":34962}},"#,
+            r#"{{"buffer":0,"byteOffset":{uvs_offset},"byteLength":{uvs_length},"target":34962}},"#,
+            r#"{{"buffer":0,"byteOffset":{indices_offset},"byteLength":{indices_length},"target":34963}}],"#,
+            r#""accessors":["#,
+            r#"{{"bufferView":0,"componentType":5126,"count":{vertex_count},"type":"VEC3","#,
+            r#""min":[{min_x},{min_y},{min_z}],"max":[{max_x},{max_y},{max_z}]}},"#,
+            r#"{{"bufferView":1,"componentType":5126,"count":{vertex_count},"type":"VEC3"}},"#,
+            r#"{{"bufferView":2,"componentType":5126,"count":{vertex_count},"type":"VEC2"}},"#,
+            r#"{{"bufferView":3,"componentType":5125,"count":{index_count},"type":"SCALAR"}}]}}"#,
+        ),
+        double_sided = material.cull_mode.is_none(),
+        r = color.red,
+        g = color.green,
+        b = color.blue,
+        a = color.alpha,
+        metallic = material.metallic,
+        roughness = material.perceptual_roughness,
+        buffer_length = bin.len(),
+        positions_length = positions_length,
+        uvs_offset = uvs_offset,
+        uvs_length = vertex_count * 8,
+        indices_offset = indices_offset,
+        indices_length = indices.len() * 4,
+        vertex_count = vertex_count,
+        index_count = indices.len(),
+        min_x = min.x,
+        min_y = min.y,
+        min_z = min.z,
+        max_x = max.x,
+        max_y = max.y,
+        max_z = max.z,
+    );
+    let mut json = json.into_bytes();
+
+    // Both chunks must be 4-byte aligned: JSON is padded with spaces, binary with zeros.
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut out = BufWriter::new(File::create(path)?);
+    out.write_all(b"glTF")?;
+    out.write_all(&2u32.to_le_bytes())?;
+    out.write_all(&(total_length as u32).to_le_bytes())?;
+    out.write_all(&(json.len() as u32).to_le_bytes())?;
+    out.write_all(b"JSON")?;
+    out.write_all(&json)?;
+    out.write_all(&(bin.len() as u32).to_le_bytes())?;
+    out.write_all(b"BIN\0")?;
+    out.write_all(&bin)?;
+    out.flush()
+}
+
+/// Writes the meshes as one watertight OBJ object on shared vertices, with the triangles of
+/// each mesh in their own group and material slot, so every face can be given its own material
+/// while the geometry stays continuous across the seams.
+pub fn export_welded_obj(meshes: &[&Mesh], path: &Path) -> io::Result<()> {
+    let (positions, triangles, ranges) = weld_faces(meshes);
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "o Planet")?;
+    for p in &positions {
+        writeln!(out, "v {} {} {}", p.x, p.y, p.z)?;
+    }
+    for (face, range) in ranges.into_iter().enumerate() {
+        writeln!(out, "g face{face}")?;
+        writeln!(out, "usemtl face{face}")?;
+        for [a, b, c] in &triangles[range] {
+            // OBJ indices are 1-based.
+            writeln!(out, "f {} {} {}", a + 1, b + 1, c + 1)?;
+        }
+    }
+    out.flush()
+}
+
+/// Writes the meshes as a single welded solid in a binary STL file, for 3D printing.
+///
+/// Welding snaps the seams between faces onto shared vertices. The faces are already wound
+/// counter-clockwise seen from outside, as STL expects, so each facet normal follows from the
+/// winding.
+pub fn export_stl(meshes: &[&Mesh], path: &Path) -> io::Result<()> {
+    let (positions, triangles) = weld(meshes);
+    let count = u32::try_from(triangles.len()).map_err(io::Error::other)?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut header = [0u8; 80];
+    let title = b"bevy_mesh planet";
+    header[..title.len()].copy_from_slice(title);
+    out.write_all(&header)?;
+    out.write_all(&count.to_le_bytes())?;
+    for triangle in &triangles {
+        let [a, b, c] = triangle.map(|i| positions[i as usize]);
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        for vector in [normal, a, b, c] {
+            for value in vector.to_array() {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+        // The attribute byte count, which is unused.
+        out.write_all(&0u16.to_le_bytes())?;
+    }
+    out.flush()
+}
+
+/// Writes the meshes as a single welded USD mesh in the ASCII `.usda` format,
+/// with smooth vertex normals and a constant display color.
+pub fn export_usda(meshes: &[&Mesh], color: Color, path: &Path) -> io::Result<()> {
+    let (positions, triangles) = weld(meshes);
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in &triangles {
+        let [a, b, c] = triangle.map(|i| positions[i as usize]);
+        // The unnormalized cross product weights each face normal by its area.
+        let normal = (b - a).cross(c - a);
+        for &i in triangle {
+            normals[i as usize] += normal;
+        }
+    }
+
+    let vec3s = |values: &[Vec3]| {
+        values
+            .iter()
+            .map(|v| format!("({}, {}, {})", v.x, v.y, v.z))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let normals: Vec<Vec3> = normals.iter().map(|n| n.normalize_or_zero()).collect();
+    let indices = triangles
+        .iter()
+        .flatten()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let counts = vec!["3"; triangles.len()].join(", ");
+    let color = color.to_linear();
+
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "#usda 1.0")?;
+    writeln!(out, "(")?;
+    writeln!(out, r#"    defaultPrim = "Planet""#)?;
+    writeln!(out, "    metersPerUnit = 1")?;
+    writeln!(out, r#"    upAxis = "Y""#)?;
+    writeln!(out, ")")?;
+    writeln!(out)?;
+    writeln!(out, r#"def Mesh "Planet""#)?;
+    writeln!(out, "{{")?;
+    writeln!(out, "    int[] faceVertexCounts = [{counts}]")?;
+    writeln!(out, "    int[] faceVertexIndices = [{indices}]")?;
+    writeln!(out, "    normal3f[] normals = [{}] (", vec3s(&normals))?;
+    writeln!(out, r#"        interpolation = "vertex""#)?;
+    writeln!(out, "    )")?;
+    writeln!(out, "    point3f[] points = [{}]", vec3s(&positions))?;
+    writeln!(
+        out,
+        "    color3f[] primvars:displayColor = [({}, {}, {})] (",
+        color.red, color.green, color.blue
+    )?;
+    writeln!(out, r#"        interpolation = "constant""#)?;
+    writeln!(out, "    )")?;
+    writeln!(out, r#"    uniform token orientation = "rightHanded""#)?;
+    writeln!(out, r#"    uniform token subdivisionScheme = "none""#)?;
+    writeln!(out, "}}")?;
+    out.flush()
+}
+
+/// Merges the meshes into one triangle list, sharing vertices that lie at the same position.
+fn weld(meshes: &[&Mesh]) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let (positions, triangles, _) = weld_faces(meshes);
+    (positions, triangles)
+}
+
+/// Merges the meshes like [`weld`], keeping each mesh's triangles together and returning the
+/// range of triangles that came from each mesh, in order.
+fn weld_faces(meshes: &[&Mesh]) -> (Vec<Vec3>, Vec<[u32; 3]>, Vec<Range<usize>>) {
+    // Every vertex of every mesh, with each mesh's triangles indexing into the combined list.
+    let mut points = Vec::new();
+    let mut corners = Vec::new();
+    for mesh in meshes {
+        let mut mesh_corners = Vec::new();
+        if let (
+            Some(VertexAttributeValues::Float32x3(mesh_positions)),
+            Some(Indices::U32(indices)),
+        ) = (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.indices())
+        {
+            let offset = points.len();
+            points.extend(mesh_positions.iter().map(|&p| Vec3::from(p)));
+            mesh_corners.extend(
+                indices
+                    .chunks_exact(3)
+                    .filter(|triangle| {
+                        triangle
+                            .iter()
+                            .all(|&i| (i as usize) < mesh_positions.len())
+                    })
+                    .map(|triangle| [0, 1, 2].map(|i| offset + triangle[i] as usize)),
+            );
+        }
+        corners.push(mesh_corners);
+    }
+    let extent = points
+        .iter()
+        .map(|point| point.length())
+        .fold(0.0, f32::max);
+    let groups = weld_groups(&points, WELD_TOLERANCE * extent);
+
+    // Each group's first point comes before the rest, so its welded index is already known.
+    let mut positions = Vec::new();
+    let mut remap = vec![0u32; points.len()];
+    for (index, &group) in groups.iter().enumerate() {
+        remap[index] = if group == index {
+            positions.push(points[index]);
+            positions.len() as u32 - 1
+        } else {
+            remap[group]
+        };
+    }
+    let mut triangles = Vec::new();
+    let mut ranges = Vec::with_capacity(corners.len());
+    for mesh_corners in corners {
+        let start = triangles.len();
+        triangles.extend(
+            mesh_corners
+                .into_iter()
+                .map(|triangle| triangle.map(|i| remap[i]))
+                // Skip triangles collapsed by welding.
+                .filter(|[a, b, c]| a != b && b != c && c != a),
+        );
+        ranges.push(start..triangles.len());
+    }
+    (positions, triangles, ranges)
+}
+
+/// Counts the edges used by exactly one triangle; a closed surface has none.
+fn open_edge_count(triangles: &[[u32; 3]]) -> usize {
+    let mut uses: HashMap<(u32, u32), u32> = HashMap::new();
+    for triangle in triangles {
+        for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+            let (a, b) = (triangle[i], triangle[j]);
+            *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+        }
+    }
+    uses.values().filter(|&&count| count == 1).count()
+}
+
+/// Writes positions and triangles as a Wavefront OBJ file.
+fn write_obj(positions: &[Vec3], triangles: &[[u32; 3]], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    for p in positions {
+        writeln!(out, "v {} {} {}", p.x, p.y, p.z)?;
+    }
+    for [a, b, c] in triangles {
+        // OBJ indices are 1-based.
+        writeln!(out, "f {} {} {}", a + 1, b + 1, c + 1)?;
+    }
+    out.flush()
+}
+
+/// Writes the edges of the meshes, projected through `view`, as SVG line elements.
+///
+/// With `hidden_line_removal`, edges are only drawn for triangles facing the camera,
+/// which hides the far side exactly for convex shapes such as the planet.
+pub fn export_svg(
+    meshes: &[&Mesh],
+    view: &SvgView,
+    hidden_line_removal: bool,
+    path: &Path,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = view.size.x,
+        h = view.size.y,
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    writeln!(
+        out,
+        r#"<g stroke="black" stroke-width="0.5" stroke-linecap="round" fill="none">"#
+    )?;
+
+    for mesh in meshes {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let Some(Indices::U32(indices)) = mesh.indices() else {
+            continue;
+        };
+
+        let projected: Vec<Option<Vec2>> = positions
+            .iter()
+            .map(|p| project(view, Vec3::from(*p)))
+            .collect();
+        let mut edges = HashSet::new();
+        for triangle in indices.chunks_exact(3) {
+            if hidden_line_removal {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+                let normal = (b - a).cross(c - a);
+                if normal.dot(view.eye - a) <= 0.0 {
+                    continue;
+                }
+            }
+            for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (triangle[i], triangle[j]);
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+
+        for (a, b) in edges {
+            if let (Some(a), Some(b)) = (projected[a as usize], projected[b as usize]) {
+                writeln!(
+                    out,
+                    r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}"/>"#,
+                    a.x, a.y, b.x, b.y
+                )?;
+            }
+        }
+    }
+
+    writeln!(out, "</g>")?;
+    writeln!(out, "</svg>")?;
+    out.flush()
+}
+
+/// Projects a point to drawing coordinates, or `None` if it is behind the camera.
+fn project(view: &SvgView, point: Vec3) -> Option<Vec2> {
+    let clip = view.clip_from_model * point.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    Some(Vec2::new(
+        (ndc.x + 1.0) * 0.5 * view.size.x,
+        (1.0 - ndc.y) * 0.5 * view.size.y,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::generation::IceCaps;
+
+    /// Returns a file in the system's temporary directory unique to this process and test.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("bevy_mesh_{}_{name}", std::process::id()))
+    }
+
+    fn planet_faces(settings: &PlanetSettings) -> Vec<Mesh> {
+        visible_face_meshes(settings).expect("the default settings are valid")
+    }
+
+    /// Splits a PLY file into its header lines and binary body.
+    fn read_ply(path: &Path) -> (Vec<String>, Vec<u8>) {
+        let bytes = fs::read(path).expect("the PLY file was written");
+        let end = b"end_header\n";
+        let split = bytes
+            .windows(end.len())
+            .position(|window| window == end)
+            .expect("the PLY file has a header")
+            + end.len();
+        let header = String::from_utf8_lossy(&bytes[..split])
+            .lines()
+            .map(str::to_string)
+            .collect();
+        (header, bytes[split..].to_vec())
+    }
+
+    fn element_count(header: &[String], element: &str) -> usize {
+        let prefix = format!("element {element} ");
+        header
+            .iter()
+            .find_map(|line| line.strip_prefix(&prefix))
+            .and_then(|count| count.parse().ok())
+            .expect("the header declares the element")
+    }
+
+    #[test]
+    fn ply_holds_every_vertex_and_triangle_with_colors() {
+        let settings = PlanetSettings {
+            resolution: 6,
+            ice_caps: IceCaps {
+                enabled: true,
+                ..default()
+            },
+            ..default()
+        };
+        let faces = planet_faces(&settings);
+        let path = temp_path("colors.ply");
+        export_ply(&faces.iter().collect::<Vec<_>>(), settings.color, &path).unwrap();
+        let (header, body) = read_ply(&path);
+        fs::remove_file(&path).ok();
+
+        let vertices: usize = faces.iter().map(Mesh::count_vertices).sum();
+        let triangles: usize = faces
+            .iter()
+            .filter_map(|mesh| mesh.indices().map(|indices| indices.len() / 3))
+            .sum();
+        assert_eq!(element_count(&header, "vertex"), vertices);
+        assert_eq!(element_count(&header, "face"), triangles);
+        // Six floats and three color bytes per vertex; a count byte and three indices per face.
+        assert_eq!(body.len(), vertices * 27 + triangles * 13);
+
+        // The vertices are written in order, each in its own color.
+        let Some(VertexAttributeValues::Float32x4(colors)) =
+            faces[0].attribute(Mesh::ATTRIBUTE_COLOR)
+        else {
+            panic!("ice caps color the vertices");
+        };
+        let index = colors.len() / 2;
+        let expected =
+            Srgba::from(LinearRgba::from_f32_array(colors[index])).to_u8_array_no_alpha();
+        let offset = index * 27 + 24;
+        assert_eq!(body[offset..offset + 3], expected);
+    }
+
+    #[test]
+    fn ply_colors_uncolored_meshes_with_the_base_color() {
+        let faces = planet_faces(&PlanetSettings::default());
+        let color = Color::srgb_u8(200, 100, 50);
+        let path = temp_path("base_color.ply");
+        export_ply(&faces.iter().collect::<Vec<_>>(), color, &path).unwrap();
+        let (_, body) = read_ply(&path);
+        fs::remove_file(&path).ok();
+        assert_eq!(body[24..27], [200, 100, 50]);
+    }
+
+    #[test]
+    fn obj_writes_vertex_colors_when_present() {
+        let settings = PlanetSettings {
+            resolution: 4,
+            ice_caps: IceCaps {
+                enabled: true,
+                ..default()
+            },
+            ..default()
+        };
+        let faces = planet_faces(&settings);
+        let path = temp_path("colors.obj");
+        export_obj(&faces.iter().collect::<Vec<_>>(), &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+        let vertex_lines: Vec<&str> = contents.lines().filter(|l| l.starts_with("v ")).collect();
+        assert_eq!(
+            vertex_lines.len(),
+            faces.iter().map(Mesh::count_vertices).sum::<usize>()
+        );
+        assert!(
+            vertex_lines
+                .iter()
+                .all(|line| line.split_whitespace().count() == 7)
+        );
+    }
+
+    /// Returns the `count` of the glTF accessor reading the given buffer view.
+    fn accessor_count(json: &str, buffer_view: usize) -> usize {
+        let accessor = format!(r#"{{"bufferView":{buffer_view},"componentType":"#);
+        let start = json.rfind(&accessor).expect("the accessor is declared");
+        let count = &json[start..];
+        let count = &count[count.find(r#""count":"#).expect("the accessor has a count") + 8..];
+        count[..count.find(',').unwrap_or(count.len())]
+            .parse()
+            .expect("the count is a number")
+    }
+
+    #[test]
+    fn glb_chunks_hold_every_vertex_and_index() {
+        let faces = planet_faces(&PlanetSettings {
+            resolution: 5,
+            ..default()
+        });
+        let meshes: Vec<&Mesh> = faces.iter().collect();
+        let path = temp_path("planet.glb");
+        export_glb(&meshes, &StandardMaterial::default(), &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let word = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+        assert_eq!(&bytes[..4], b"glTF");
+        assert_eq!(word(4), 2);
+        assert_eq!(word(8), bytes.len());
+        let json_length = word(12);
+        assert_eq!(&bytes[16..20], b"JSON");
+        let json = std::str::from_utf8(&bytes[20..20 + json_length]).unwrap();
+        let bin_start = 20 + json_length;
+        assert_eq!(&bytes[bin_start + 4..bin_start + 8], b"BIN\0");
+        let bin = &bytes[bin_start + 8..bin_start + 8 + word(bin_start)];
+        assert_eq!(bin_start + 8 + bin.len(), bytes.len());
+
+        let vertex_count: usize = faces.iter().map(Mesh::count_vertices).sum();
+        let index_count: usize = faces
+            .iter()
+            .filter_map(Mesh::indices)
+            .map(|i| i.len())
+            .sum();
+        assert_eq!(accessor_count(json, 0), vertex_count);
+        assert_eq!(accessor_count(json, 1), vertex_count);
+        assert_eq!(accessor_count(json, 2), vertex_count);
+        assert_eq!(accessor_count(json, 3), index_count);
+        assert_eq!(bin.len(), vertex_count * (12 + 12 + 8) + index_count * 4);
+
+        let float = |at: usize| f32::from_le_bytes(bin[at..at + 4].try_into().unwrap());
+        let Some(VertexAttributeValues::Float32x3(first)) =
+            faces[0].attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("the face has positions");
+        };
+        assert_eq!([float(0), float(4), float(8)], first[0]);
+        let indices = &bin[vertex_count * 32..];
+        assert!(
+            indices
+                .chunks_exact(4)
+                .all(
+                    |index| (u32::from_le_bytes(index.try_into().unwrap()) as usize) < vertex_count
+                )
+        );
+    }
+
+    #[test]
+    fn stl_declares_one_facet_per_triangle() {
+        let faces = planet_faces(&PlanetSettings {
+            resolution: 7,
+            ..default()
+        });
+        let meshes: Vec<&Mesh> = faces.iter().collect();
+        let path = temp_path("planet.stl");
+        export_stl(&meshes, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let index_count: usize = faces
+            .iter()
+            .filter_map(Mesh::indices)
+            .map(|i| i.len())
+            .sum();
+        let declared = u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize;
+        assert_eq!(declared, index_count / 3);
+        // Each facet is a normal, three corners and an attribute byte count.
+        assert_eq!(bytes.len(), 84 + declared * (4 * 12 + 2));
+    }
+
+    #[test]
+    fn svg_view_turns_with_the_planet() {
+        let camera = GlobalTransform::from_translation(Vec3::new(0.0, 0.0, 5.0));
+        let spin = Mat4::from_rotation_y(FRAC_PI_2);
+        let view = SvgView::new(Mat4::IDENTITY, &camera, spin, Vec2::splat(100.0));
+        // Turning the planet a quarter turn brings its -X side round to face the camera.
+        assert!(view.eye.abs_diff_eq(Vec3::new(-5.0, 0.0, 0.0), 1e-5));
+        let front = view.clip_from_model * Vec3::NEG_X.extend(1.0);
+        assert!(
+            front
+                .truncate()
+                .abs_diff_eq(Vec3::new(0.0, 0.0, -4.0), 1e-5)
+        );
+    }
+
+    #[test]
+    fn welding_closes_the_seams_at_any_radius() {
+        let resolution = 9;
+        for radius in [1.0, 1000.0] {
+            let settings = PlanetSettings {
+                resolution,
+                radius,
+                spherify: true,
+                ..default()
+            };
+            let faces = planet_faces(&settings);
+            let (positions, triangles) = weld(&faces.iter().collect::<Vec<_>>());
+            assert_eq!(open_edge_count(&triangles), 0, "radius {radius}");
+            // The six faces share their edges and corners.
+            let r = resolution as usize;
+            assert_eq!(positions.len(), 6 * r * r - 12 * r + 8, "radius {radius}");
+        }
+    }
+
+    #[test]
+    fn welded_obj_shares_vertices_across_six_face_groups() {
+        let resolution = 7;
+        let settings = PlanetSettings {
+            resolution,
+            spherify: true,
+            ..default()
+        };
+        let faces = planet_faces(&settings);
+        let face_refs: Vec<&Mesh> = faces.iter().collect();
+        let (positions, triangles, ranges) = weld_faces(&face_refs);
+        let r = resolution as usize;
+        assert_eq!(positions.len(), 6 * r * r - 12 * r + 8);
+        assert_eq!(ranges.len(), 6);
+        let mut next = 0;
+        for range in &ranges {
+            assert_eq!(range.start, next);
+            assert_eq!(range.len(), (r - 1) * (r - 1) * 2);
+            next = range.end;
+        }
+        assert_eq!(next, triangles.len());
+        assert_eq!(open_edge_count(&triangles), 0);
+
+        let path = temp_path("welded.obj");
+        export_welded_obj(&face_refs, &path).unwrap();
+        let obj = fs::read_to_string(&path).unwrap();
+        let count = |prefix: &str| obj.lines().filter(|line| line.starts_with(prefix)).count();
+        assert_eq!(count("v "), positions.len());
+        assert_eq!(count("f "), triangles.len());
+        assert_eq!(count("g "), 6);
+        assert_eq!(count("usemtl "), 6);
+        fs::remove_file(&path).ok();
+    }
+}
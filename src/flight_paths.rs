@@ -0,0 +1,221 @@
+use bevy::input::mouse::MouseButton;
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use crate::picking::PlanetPicker;
+use crate::{BaseShape, PlanetSettings, PlanetSpin, surface_point};
+
+/// How far the arcs float above the surface, relative to the planet radius.
+const ARC_LIFT: f32 = 0.02;
+
+/// The largest angle, in radians, spanned by one segment of a drawn arc.
+const ARC_SEGMENT_ANGLE: f32 = 0.02;
+
+const ARC_COLOR: Color = Color::srgb(1.0, 0.75, 0.2);
+const MARKER_COLOR: Color = Color::srgb(1.0, 0.3, 0.2);
+const MARKER_RADIUS: f32 = 0.015;
+
+/// A route between two surface points along the great circle through them.
+#[derive(Debug, Clone, Copy)]
+pub struct FlightPath {
+    /// The unit direction of the departure point.
+    pub from: Vec3,
+    /// The unit direction of the arrival point.
+    pub to: Vec3,
+    /// How far the marker has traveled, from 0 at `from` to 1 at `to`.
+    progress: f32,
+}
+
+impl FlightPath {
+    fn new(from: Vec3, to: Vec3) -> Self {
+        Self {
+            from,
+            to,
+            progress: 0.0,
+        }
+    }
+
+    /// The angle between the endpoints, which is the arc length on the unit sphere.
+    pub fn angle(&self) -> f32 {
+        self.from.angle_between(self.to)
+    }
+
+    /// Returns the unit direction at `t` along the arc, from 0 at `from` to 1 at `to`.
+    fn direction_at(&self, t: f32) -> Vec3 {
+        // Rotating about the circle's axis keeps the points on the great circle; antipodal
+        // endpoints are joined by any circle through both.
+        let axis = self
+            .from
+            .cross(self.to)
+            .try_normalize()
+            .unwrap_or_else(|| self.from.any_orthonormal_vector());
+        Quat::from_axis_angle(axis, self.angle() * t) * self.from
+    }
+}
+
+/// A resource holding the flight paths and the tool that places them.
+#[derive(Resource, Debug)]
+pub struct FlightPaths {
+    pub paths: Vec<FlightPath>,
+    /// Whether left clicks on the planet place path endpoints.
+    pub placing: bool,
+    /// The departure point waiting for its arrival click.
+    pending: Option<Vec3>,
+    pub animate: bool,
+    /// How fast markers travel, in radians of arc per second.
+    pub speed: f32,
+    /// Whether markers start over after arriving.
+    pub looping: bool,
+}
+
+impl Default for FlightPaths {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            placing: false,
+            pending: None,
+            animate: true,
+            speed: 0.5,
+            looping: true,
+        }
+    }
+}
+
+pub struct FlightPathPlugin;
+
+impl Plugin for FlightPathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlightPaths>()
+            .add_systems(
+                Update,
+                (place_flight_path, advance_markers, draw_flight_paths),
+            )
+            .add_systems(EguiPrimaryContextPass, ui_flight_paths);
+    }
+}
+
+/// Picks a departure point on the first click and completes the path on the second.
+fn place_flight_path(
+    mut contexts: EguiContexts,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut flight_paths: ResMut<FlightPaths>,
+    mut picker: PlanetPicker,
+) {
+    if !flight_paths.placing || !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_pointer_input()
+    {
+        return;
+    }
+    let Some(point) = picker.cursor_point() else {
+        return;
+    };
+    let direction = point.normalize();
+    match flight_paths.pending.take() {
+        Some(from) => flight_paths.paths.push(FlightPath::new(from, direction)),
+        None => flight_paths.pending = Some(direction),
+    }
+}
+
+/// Moves each marker along its arc at a constant ground speed.
+fn advance_markers(time: Res<Time>, mut flight_paths: ResMut<FlightPaths>) {
+    if !flight_paths.animate {
+        return;
+    }
+    let distance = flight_paths.speed * time.delta_secs();
+    let looping = flight_paths.looping;
+    for path in &mut flight_paths.paths {
+        let angle = path.angle().max(f32::EPSILON);
+        path.progress += distance / angle;
+        if path.progress > 1.0 {
+            path.progress = if looping { path.progress.fract() } else { 1.0 };
+        }
+    }
+}
+
+fn draw_flight_paths(
+    mut gizmos: Gizmos,
+    flight_paths: Res<FlightPaths>,
+    settings: Res<PlanetSettings>,
+    spin: Res<PlanetSpin>,
+) {
+    if settings.shape == BaseShape::Plane {
+        return;
+    }
+    // Gizmos are drawn in world space, so the arcs are turned with the planet.
+    let rotation = Quat::from_rotation_y(spin.angle);
+    let lifted = |direction: Vec3| {
+        let (point, normal) = surface_point(direction, &settings);
+        rotation * (point + normal * ARC_LIFT * settings.radius)
+    };
+    for path in &flight_paths.paths {
+        let segments = (path.angle() / ARC_SEGMENT_ANGLE).ceil().max(1.0) as u32;
+        gizmos.linestrip(
+            (0..=segments).map(|i| lifted(path.direction_at(i as f32 / segments as f32))),
+            ARC_COLOR,
+        );
+        if flight_paths.animate {
+            let marker = lifted(path.direction_at(path.progress));
+            gizmos.sphere(
+                Isometry3d::from_translation(marker),
+                MARKER_RADIUS * settings.radius,
+                MARKER_COLOR,
+            );
+        }
+    }
+    if let Some(from) = flight_paths.pending {
+        gizmos.sphere(
+            Isometry3d::from_translation(lifted(from)),
+            MARKER_RADIUS * settings.radius,
+            ARC_COLOR,
+        );
+    }
+}
+
+/// A window listing the flight paths with their lengths and the animation options.
+fn ui_flight_paths(mut contexts: EguiContexts, mut flight_paths: ResMut<FlightPaths>) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Flight Paths")
+        .default_open(false)
+        .show(ctx, |ui| {
+            if ui
+                .checkbox(&mut flight_paths.placing, "Place with two left clicks")
+                .changed()
+            {
+                flight_paths.pending = None;
+            }
+            ui.checkbox(&mut flight_paths.animate, "Animate markers");
+            ui.add_enabled_ui(flight_paths.animate, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut flight_paths.speed, 0.05..=3.0).text("Speed (rad/s)"),
+                );
+                ui.checkbox(&mut flight_paths.looping, "Loop");
+            });
+
+            ui.separator();
+
+            let mut removed = None;
+            for (index, path) in flight_paths.paths.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Path {}: {:.1}°",
+                        index + 1,
+                        path.angle().to_degrees()
+                    ));
+                    if ui.small_button("Remove").clicked() {
+                        removed = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = removed {
+                flight_paths.paths.remove(index);
+            }
+            if flight_paths.paths.is_empty() {
+                ui.label("No paths placed.");
+            } else if ui.button("Clear Paths").clicked() {
+                flight_paths.paths.clear();
+            }
+        });
+}
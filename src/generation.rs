@@ -0,0 +1,2036 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::Face;
+use serde::{Deserialize, Serialize};
+
+use crate::elevation::apply_elevation_colors;
+use crate::erosion::ErosionMap;
+use crate::ice_caps::apply_ice_caps;
+use crate::noise::{fbm, varied_seed_offset};
+use crate::persistence;
+use crate::sculpt::apply_height_layer;
+
+pub use crate::elevation::{ElevationColors, GradientStop};
+pub use crate::erosion::Erosion;
+pub use crate::ice_caps::IceCaps;
+pub use crate::noise::{NoiseLayer, NoiseMode};
+pub use crate::sculpt::{HeightLayer, HeightStamp};
+pub use crate::texture::ProceduralTexture;
+pub use crate::toon::{Shading, ToonShading};
+pub use crate::wireframe::WireframeMode;
+
+/// The smallest grid resolution that still forms triangles.
+pub const MIN_RESOLUTION: u32 = 2;
+
+/// The largest grid resolution whose six indices per quad can all be counted in 32 bits.
+pub const MAX_RESOLUTION: u32 = 26_755;
+
+/// The most subdivisions Bevy's icosphere can be generated with.
+pub const MAX_ICOSPHERE_SUBDIVISIONS: u32 = 79;
+
+/// Why a mesh could not be generated.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_mesh::generation::{MAX_RESOLUTION, MeshGenError, NormalShading, create_face_mesh};
+///
+/// let smooth = NormalShading::Smooth;
+/// let flat = |_: Vec3| 0.0;
+/// assert_eq!(
+///     create_face_mesh(1, Vec3::Y, true, 0.0, 1.0, smooth, flat).err(),
+///     Some(MeshGenError::ResolutionTooLow(1))
+/// );
+/// assert_eq!(
+///     create_face_mesh(MAX_RESOLUTION + 1, Vec3::Y, true, 0.0, 1.0, smooth, flat).err(),
+///     Some(MeshGenError::ResolutionTooHigh(MAX_RESOLUTION + 1))
+/// );
+/// let normal = Vec3::new(0.0, 2.0, 0.0);
+/// assert_eq!(
+///     create_face_mesh(8, normal, true, 0.0, 1.0, smooth, flat).err(),
+///     Some(MeshGenError::NormalNotUnit(normal))
+/// );
+/// assert!(create_face_mesh(8, Vec3::Y, true, 0.0, 1.0, smooth, flat).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeshGenError {
+    /// Fewer than [`MIN_RESOLUTION`] vertices along an edge, which span no triangles.
+    ResolutionTooLow(u32),
+    /// More vertices or indices than 32 bits can count, beyond [`MAX_RESOLUTION`] for a grid or
+    /// [`MAX_ICOSPHERE_SUBDIVISIONS`] for the icosphere.
+    ResolutionTooHigh(u32),
+    /// A face normal that is not of unit length.
+    NormalNotUnit(Vec3),
+    /// A flattening outside `0.0..1.0`, which would squash the planet flat or inside out.
+    FlatteningOutOfRange(f32),
+    /// A planet radius that is not a positive number.
+    RadiusNotPositive(f32),
+    /// A plane side length that is not a positive number.
+    PlaneSizeNotPositive(f32),
+    /// An erosion strength that is not a number.
+    ErosionStrengthNotFinite(f32),
+    /// A variation outside `0.0..=1.0`.
+    VariationOutOfRange(f32),
+    /// A lateral displacement strength that is not a number.
+    LateralStrengthNotFinite(f32),
+    /// A noise layer, by index, with a parameter that is not a number.
+    NoiseLayerNotFinite(usize),
+}
+
+impl fmt::Display for MeshGenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ResolutionTooLow(resolution) => write!(
+                f,
+                "resolution {resolution} is below the minimum of {MIN_RESOLUTION}"
+            ),
+            Self::ResolutionTooHigh(resolution) => write!(
+                f,
+                "resolution {resolution} yields more vertices than the mesh can index"
+            ),
+            Self::NormalNotUnit(normal) => write!(f, "face normal {normal} is not of unit length"),
+            Self::FlatteningOutOfRange(flattening) => {
+                write!(f, "flattening {flattening} is outside 0 to 1")
+            }
+            Self::RadiusNotPositive(radius) => {
+                write!(f, "radius {radius} is not a positive number")
+            }
+            Self::PlaneSizeNotPositive(size) => {
+                write!(f, "plane size {size} is not a positive number")
+            }
+            Self::ErosionStrengthNotFinite(strength) => {
+                write!(f, "erosion strength {strength} is not a number")
+            }
+            Self::VariationOutOfRange(variation) => {
+                write!(f, "variation {variation} is outside 0 to 1")
+            }
+            Self::LateralStrengthNotFinite(strength) => {
+                write!(f, "lateral strength {strength} is not a number")
+            }
+            Self::NoiseLayerNotFinite(index) => {
+                write!(f, "noise layer {index} has a non-finite parameter")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshGenError {}
+
+/// The outward normals of the six cube faces that make up the planet.
+pub const FACE_DIRECTIONS: [Vec3; 6] = [
+    Vec3::Y,
+    Vec3::NEG_Y,
+    Vec3::NEG_X,
+    Vec3::X,
+    Vec3::Z,
+    Vec3::NEG_Z,
+];
+
+/// The smallest radius noise displacement can carve the surface down to.
+const MIN_NOISE_RADIUS: f32 = 0.05;
+
+/// How often the sideways displacement field turns, across the planet radius.
+const LATERAL_FREQUENCY: f32 = 3.0;
+
+/// The step across a face, as a fraction of its width, used to take normals from the surface.
+const NORMAL_STEP: f32 = 1e-3;
+
+/// A resource to hold the settings for our procedurally generated planet.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanetSettings {
+    pub resolution: u32,
+    pub shape: BaseShape,
+    pub spherify: bool,
+    pub flattening: f32,
+    /// The radius of the planet in world units; the noise, sculpting and ice caps are relative
+    /// to it.
+    pub radius: f32,
+    pub normal_shading: NormalShading,
+    /// Selects the terrain noise, so the same seed always yields the same surface.
+    pub seed: u32,
+    /// Nudges the seed's noise from 0 to 1, for planets close to the seed's.
+    pub variation: f32,
+    /// Fractal noise layers summed to displace the surface.
+    pub noise_layers: Vec<NoiseLayer>,
+    /// Raindrops wearing valleys into the noise terrain.
+    pub erosion: Erosion,
+    /// How far the noise terrain also shifts sideways along the surface, for cliffs and
+    /// overhangs; 0 keeps the displacement purely radial.
+    pub lateral_strength: f32,
+    /// Vertices of different faces closer than this, relative to the radius, share their
+    /// averaged normal; 0 disables.
+    pub seam_weld_epsilon: f32,
+    pub uv_projection: UvProjection,
+    /// The side length of the flat plane shape, relative to the radius.
+    pub plane_size: f32,
+    pub wireframe: bool,
+    pub wireframe_depth_bias: f32,
+    pub wireframe_mode: WireframeMode,
+    pub wireframe_width: f32,
+    #[serde(with = "persistence::srgba")]
+    pub wireframe_color: Color,
+    #[serde(with = "persistence::srgba")]
+    pub color: Color,
+    pub metallic: f32,
+    /// The perceptual roughness of the surface, from glossy at 0 to matte at 1.
+    pub roughness: f32,
+    pub texture: ProceduralTexture,
+    pub shading: Shading,
+    pub toon: ToonShading,
+    pub ice_caps: IceCaps,
+    pub elevation_colors: ElevationColors,
+    pub show_ocean: bool,
+    /// The radius of the translucent sea-level sphere, relative to the planet radius.
+    pub ocean_level: f32,
+    pub sculpt: HeightLayer,
+    pub culling: Culling,
+    pub highlight_backfaces: bool,
+    pub mesh_memory: MeshMemory,
+}
+
+impl Default for PlanetSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 10,
+            shape: BaseShape::Planet,
+            spherify: true,
+            flattening: 0.0,
+            radius: 1.0,
+            normal_shading: NormalShading::Smooth,
+            seed: 0,
+            variation: 0.0,
+            noise_layers: Vec::new(),
+            erosion: Erosion::default(),
+            lateral_strength: 0.0,
+            seam_weld_epsilon: 1e-5,
+            uv_projection: UvProjection::FaceGrid,
+            plane_size: 4.0,
+            wireframe: false,
+            wireframe_depth_bias: 100.0,
+            wireframe_mode: WireframeMode::Lines,
+            wireframe_width: 1.0,
+            wireframe_color: Color::WHITE,
+            color: Color::srgb(0.5, 0.5, 0.6),
+            metallic: 0.0,
+            roughness: 0.5,
+            texture: ProceduralTexture::default(),
+            shading: Shading::Pbr,
+            toon: ToonShading::default(),
+            ice_caps: IceCaps::default(),
+            elevation_colors: ElevationColors::default(),
+            show_ocean: false,
+            ocean_level: 1.0,
+            sculpt: HeightLayer::default(),
+            culling: Culling::Back,
+            highlight_backfaces: false,
+            mesh_memory: MeshMemory::Auto,
+        }
+    }
+}
+
+impl PlanetSettings {
+    /// Lists the fields that differ from the defaults, with their current values.
+    pub(crate) fn non_default_fields(&self) -> Vec<(&'static str, String)> {
+        let defaults = Self::default();
+        let mut fields = Vec::new();
+        // Not every field type implements `PartialEq`, so fields are compared by their
+        // debug output.
+        macro_rules! compare {
+            ($($field:ident),* $(,)?) => {$(
+                let value = format!("{:?}", self.$field);
+                if value != format!("{:?}", defaults.$field) {
+                    fields.push((stringify!($field), value));
+                }
+            )*};
+        }
+        compare!(
+            resolution,
+            shape,
+            spherify,
+            flattening,
+            radius,
+            normal_shading,
+            seed,
+            variation,
+            erosion,
+            lateral_strength,
+            seam_weld_epsilon,
+            uv_projection,
+            plane_size,
+            wireframe,
+            wireframe_depth_bias,
+            wireframe_mode,
+            wireframe_width,
+            wireframe_color,
+            color,
+            metallic,
+            roughness,
+            texture,
+            shading,
+            toon,
+            ice_caps,
+            elevation_colors,
+            show_ocean,
+            ocean_level,
+            culling,
+            highlight_backfaces,
+            mesh_memory,
+        );
+        // The painted layer can hold thousands of stamps, so only their number is listed.
+        if !self.sculpt.stamps.is_empty() {
+            fields.push(("sculpt", format!("{} stamps", self.sculpt.stamps.len())));
+        }
+        if !self.noise_layers.is_empty() {
+            fields.push((
+                "noise_layers",
+                format!("{} layers", self.noise_layers.len()),
+            ));
+        }
+        fields
+    }
+
+    /// Returns how close, in world units, vertices of different faces must be to share a
+    /// normal.
+    pub(crate) fn seam_weld_tolerance(&self) -> f32 {
+        self.seam_weld_epsilon * self.radius
+    }
+
+    /// Returns whether the normals of the planet faces are averaged along their seams; the
+    /// unspherified cube keeps its hard edges and flat facets keep their own normals.
+    pub(crate) fn welds_seams(&self) -> bool {
+        self.shape == BaseShape::Planet
+            && self.spherify
+            && self.normal_shading == NormalShading::Smooth
+    }
+
+    /// Returns the enabled noise layers that shape the surface: the soloed ones if any are,
+    /// otherwise those that aren't muted.
+    pub fn audible_noise_layers(&self) -> impl Iterator<Item = &NoiseLayer> {
+        let enabled = || self.noise_layers.iter().filter(|layer| layer.enabled);
+        let soloing = enabled().any(|layer| layer.solo);
+        enabled().filter(move |layer| if soloing { layer.solo } else { !layer.mute })
+    }
+
+    /// Returns the noise displacement at the given unit direction, relative to the radius,
+    /// summed over the [audible layers](Self::audible_noise_layers) and carved by erosion.
+    ///
+    /// Deep valleys are clamped so the surface never passes through the center. Use
+    /// [`noise_height_fn`](Self::noise_height_fn) to sample many directions.
+    pub fn noise_height(&self, direction: Vec3) -> f32 {
+        self.noise_height_fn()(direction)
+    }
+
+    /// Returns [`noise_height`](Self::noise_height) as a function of the direction, looking up
+    /// the erosion once rather than for every sample.
+    pub fn noise_height_fn(&self) -> impl Fn(Vec3) -> f32 + '_ {
+        let offset = self.noise_offset();
+        let erosion = (self.erosion.enabled && self.audible_noise_layers().next().is_some())
+            .then(|| ErosionMap::cached(self));
+        move |direction| {
+            let mut height = self.layered_noise_height(direction, offset);
+            if let Some(erosion) = &erosion {
+                height += erosion.height_change(direction);
+            }
+            height.max(MIN_NOISE_RADIUS - 1.0)
+        }
+    }
+
+    /// Returns where the seed and the variation shift the noise; see [`varied_seed_offset`].
+    pub fn noise_offset(&self) -> Vec3 {
+        varied_seed_offset(self.seed, self.variation)
+    }
+
+    /// Returns the sideways shift of the surface at the given unit direction, relative to the
+    /// radius: a noise field along the surface, scaled by the noise height there and by
+    /// [`lateral_strength`](Self::lateral_strength).
+    pub fn lateral_offset_fn(&self) -> impl Fn(Vec3) -> Vec3 + '_ {
+        let offset = self.noise_offset();
+        let noise_height = self.noise_height_fn();
+        move |direction| {
+            let point = direction * LATERAL_FREQUENCY + offset;
+            let field = Vec3::new(
+                fbm(point, 3),
+                fbm(point + Vec3::splat(17.0), 3),
+                fbm(point - Vec3::splat(31.0), 3),
+            ) * 2.0
+                - 1.0;
+            field.reject_from_normalized(direction)
+                * noise_height(direction)
+                * self.lateral_strength
+        }
+    }
+
+    /// Returns the sum of the audible layers at the given unit direction, before erosion and
+    /// clamping, with the noise shifted by the seed's `offset`.
+    pub(crate) fn layered_noise_height(&self, direction: Vec3, offset: Vec3) -> f32 {
+        self.audible_noise_layers()
+            .map(|layer| layer.height(direction, offset))
+            .sum()
+    }
+}
+
+/// The base surface that is generated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BaseShape {
+    /// Six cube faces, optionally spherified.
+    #[default]
+    Planet,
+    /// A single flat grid centered on the origin, facing up.
+    Plane,
+    /// Bevy's built-in UV sphere with `resolution` stacks, as a known-good reference
+    /// when comparing lighting and shading against the generated planet.
+    UvSphere,
+    /// A single icosahedron with each edge split `resolution` times, whose triangles are
+    /// spread far more evenly than the cube faces'.
+    Icosphere,
+}
+
+impl BaseShape {
+    /// Returns whether the shape is a planet, raised and colored by the terrain settings.
+    pub fn is_planet(self) -> bool {
+        matches!(self, BaseShape::Planet | BaseShape::Icosphere)
+    }
+}
+
+/// How texture coordinates are laid over the planet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UvProjection {
+    /// Each face spans the whole texture, following its grid.
+    #[default]
+    FaceGrid,
+    /// Longitude and latitude, so one texture wraps around the whole planet.
+    Equirectangular,
+}
+
+/// How the planet faces are lit across their triangles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormalShading {
+    /// Normals are shared between triangles, so the surface looks curved.
+    #[default]
+    Smooth,
+    /// Every triangle has its own vertices with its face normal, for a faceted low-poly look.
+    /// This triples the vertex count.
+    Flat,
+}
+
+/// Which side of the planet's triangles is skipped when drawing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Culling {
+    #[default]
+    Back,
+    Front,
+    None,
+}
+
+impl Culling {
+    pub(crate) fn face(self) -> Option<Face> {
+        match self {
+            Culling::Back => Some(Face::Back),
+            Culling::Front => Some(Face::Front),
+            Culling::None => None,
+        }
+    }
+}
+
+/// Where the generated face meshes are kept once uploaded to the GPU.
+///
+/// A main-world copy doubles the memory of every face mesh, but the picking tools and the
+/// barycentric wireframe read vertices on the CPU and need one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeshMemory {
+    /// Keeps a CPU copy only while a feature that reads the meshes is active.
+    #[default]
+    Auto,
+    /// Always keeps a CPU copy alongside the GPU buffers.
+    MainAndRender,
+    /// Frees the CPU copy after upload; features reading the meshes stop working.
+    RenderOnly,
+}
+
+impl MeshMemory {
+    pub(crate) fn usage(self, cpu_access_needed: bool) -> RenderAssetUsages {
+        match self {
+            MeshMemory::Auto if cpu_access_needed => RenderAssetUsages::default(),
+            MeshMemory::MainAndRender => RenderAssetUsages::default(),
+            MeshMemory::Auto | MeshMemory::RenderOnly => RenderAssetUsages::RENDER_WORLD,
+        }
+    }
+}
+
+/// A component to identify a face of the planet and store its primary direction.
+#[derive(Component)]
+pub struct PlanetFace {
+    pub normal: Vec3,
+}
+
+/// Generates the mesh of the face with the given normal for the selected base shape.
+///
+/// The flat plane, the UV sphere and the icosphere are drawn by the upward face alone; the
+/// other faces get a minimal cube face since they are hidden.
+pub fn surface_mesh(settings: &PlanetSettings, normal: Vec3) -> Result<Mesh, MeshGenError> {
+    let mut mesh = match settings.shape {
+        BaseShape::Planet => {
+            let mut mesh = create_face_mesh(
+                settings.resolution,
+                normal,
+                settings.spherify,
+                settings.flattening,
+                settings.radius,
+                settings.normal_shading,
+                settings.noise_height_fn(),
+            )?;
+            apply_terrain(&mut mesh, settings);
+            mesh
+        }
+        BaseShape::Icosphere if normal == Vec3::Y => {
+            let mut mesh = create_icosphere_mesh(
+                settings.resolution,
+                settings.flattening,
+                settings.radius,
+                settings.normal_shading,
+                settings.noise_height_fn(),
+            )?;
+            apply_terrain(&mut mesh, settings);
+            mesh
+        }
+        BaseShape::Plane if normal == Vec3::Y => {
+            create_plane_mesh(settings.resolution, settings.plane_size * settings.radius)?
+        }
+        BaseShape::UvSphere if normal == Vec3::Y => {
+            create_uv_sphere_mesh(settings.resolution, settings.radius)?
+        }
+        BaseShape::Plane | BaseShape::UvSphere | BaseShape::Icosphere => create_face_mesh(
+            MIN_RESOLUTION,
+            normal,
+            false,
+            0.0,
+            1.0,
+            NormalShading::Smooth,
+            |_| 0.0,
+        )?,
+    };
+    repair_non_finite_vertices(&mut mesh, settings.radius, normal);
+    add_tangents(&mut mesh, settings, normal);
+    Ok(mesh)
+}
+
+/// Lays the planet's UVs, sculpted elevation, elevation colors and ice caps over a mesh
+/// already raised by the noise.
+fn apply_terrain(mesh: &mut Mesh, settings: &PlanetSettings) {
+    if settings.uv_projection == UvProjection::Equirectangular {
+        apply_equirectangular_uvs(mesh);
+    }
+    apply_height_layer(mesh, &settings.sculpt);
+    // Sculpting tilts every vertex on its own, so the facets are flattened again.
+    if settings.normal_shading == NormalShading::Flat && !settings.sculpt.stamps.is_empty() {
+        apply_flat_shading(mesh);
+    }
+    if settings.elevation_colors.enabled {
+        let noise_height = settings.noise_height_fn();
+        apply_elevation_colors(mesh, &settings.elevation_colors, |direction| {
+            (1.0 + noise_height(direction)) * (1.0 + settings.sculpt.height(direction)) - 1.0
+        });
+    }
+    let ice_caps = &settings.ice_caps;
+    if ice_caps.enabled {
+        apply_ice_caps(mesh, ice_caps, settings.seed, settings.color);
+    }
+    apply_lateral_displacement(mesh, settings);
+    // Raised ice sheets and sideways shifts move the vertices off the surface the normals
+    // were taken from.
+    if (ice_caps.enabled && ice_caps.thickness != 0.0) || settings.lateral_strength != 0.0 {
+        match settings.normal_shading {
+            NormalShading::Smooth => recompute_smooth_normals(mesh),
+            NormalShading::Flat => apply_flat_shading(mesh),
+        }
+    }
+}
+
+/// Shifts every vertex sideways by [`PlanetSettings::lateral_offset_fn`]. Vertices shared
+/// along the seams shift alike, so the faces still meet.
+fn apply_lateral_displacement(mesh: &mut Mesh, settings: &PlanetSettings) {
+    if settings.lateral_strength == 0.0 {
+        return;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let lateral_offset = settings.lateral_offset_fn();
+    for position in positions.iter_mut() {
+        let point = Vec3::from(*position);
+        let offset = lateral_offset(point.normalize_or_zero()) * settings.radius;
+        *position = (point + offset).into();
+    }
+}
+
+/// Adds tangents following the U direction of the mesh's UVs, so normal maps can be applied.
+///
+/// The generated grids have an analytic tangent frame, which avoids the cost of averaging
+/// over triangles; only Bevy's spheres go through `Mesh::generate_tangents`.
+fn add_tangents(mesh: &mut Mesh, settings: &PlanetSettings, normal: Vec3) {
+    if mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_none() {
+        warn!("Skipping tangents for a mesh without UVs");
+        return;
+    }
+    match settings.shape {
+        BaseShape::UvSphere | BaseShape::Icosphere if normal == Vec3::Y => {
+            if let Err(err) = mesh.generate_tangents() {
+                warn!("Failed to generate tangents: {err}");
+            }
+        }
+        // U runs east with longitude while V runs south, against the bitangent.
+        BaseShape::Planet if settings.uv_projection == UvProjection::Equirectangular => {
+            insert_analytic_tangents(mesh, |point| Vec3::new(point.z, 0.0, -point.x), -1.0);
+        }
+        _ => {
+            let scale = if settings.shape == BaseShape::Planet {
+                polar_scale(settings.flattening)
+            } else {
+                Vec3::ONE
+            };
+            let (axis_a, _) = face_axes(normal);
+            insert_analytic_tangents(mesh, |_| axis_a * scale, 1.0);
+        }
+    }
+}
+
+/// Inserts tangents along `u_direction` at each vertex position, made perpendicular to the
+/// vertex normal, with the given bitangent handedness.
+fn insert_analytic_tangents(mesh: &mut Mesh, u_direction: impl Fn(Vec3) -> Vec3, handedness: f32) {
+    let (
+        Some(VertexAttributeValues::Float32x3(positions)),
+        Some(VertexAttributeValues::Float32x3(normals)),
+    ) = (
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+    )
+    else {
+        return;
+    };
+    let tangents: Vec<[f32; 4]> = positions
+        .iter()
+        .zip(normals)
+        .map(|(&position, &normal)| {
+            let normal = Vec3::from(normal);
+            let tangent = u_direction(Vec3::from(position))
+                .reject_from_normalized(normal)
+                .try_normalize()
+                // Poles have no east, so any direction along the surface will do.
+                .unwrap_or_else(|| normal.any_orthonormal_vector());
+            tangent.extend(handedness).to_array()
+        })
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, tangents);
+}
+
+/// Replaces NaN or infinite positions and normals, which break rendering and export,
+/// warning about how many were found.
+///
+/// A broken position is moved onto the sphere of the given `radius`, in the direction of its
+/// finite neighbors, or failing that of its normal or the `face_normal`.
+fn repair_non_finite_vertices(mesh: &mut Mesh, radius: f32, face_normal: Vec3) {
+    let (
+        Some(VertexAttributeValues::Float32x3(positions)),
+        Some(VertexAttributeValues::Float32x3(normals)),
+    ) = (
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+    )
+    else {
+        return;
+    };
+    let mut positions: Vec<Vec3> = positions.iter().copied().map(Vec3::from).collect();
+    let mut normals: Vec<Vec3> = normals.iter().copied().map(Vec3::from).collect();
+    if positions.iter().chain(&normals).all(|v| v.is_finite()) {
+        return;
+    }
+
+    // The sum of each vertex's finite neighbors, which points the way its part of the surface
+    // lies.
+    let mut neighbors = vec![Vec3::ZERO; positions.len()];
+    if let Some(Indices::U32(indices)) = mesh.indices() {
+        for triangle in indices.chunks_exact(3) {
+            for &i in triangle {
+                for &j in triangle {
+                    let (Some(&point), Some(sum)) =
+                        (positions.get(j as usize), neighbors.get_mut(i as usize))
+                    else {
+                        continue;
+                    };
+                    if j != i && point.is_finite() {
+                        *sum += point;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut repaired = 0;
+    for ((position, normal), neighbors) in positions.iter_mut().zip(&mut normals).zip(neighbors) {
+        if position.is_finite() && normal.is_finite() {
+            continue;
+        }
+        if !position.is_finite() {
+            let direction = neighbors
+                .try_normalize()
+                .or_else(|| normal.try_normalize())
+                .unwrap_or(face_normal);
+            *position = direction * radius;
+        }
+        if !normal.is_finite() {
+            *normal = position.normalize_or(face_normal);
+        }
+        repaired += 1;
+    }
+    warn!("Repaired {repaired} vertices with non-finite positions or normals");
+    let positions: Vec<[f32; 3]> = positions.into_iter().map(Into::into).collect();
+    let normals: Vec<[f32; 3]> = normals.into_iter().map(Into::into).collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+}
+
+/// Hides every face but the upward one for every base shape drawn by the upward face alone.
+pub fn face_visibility(settings: &PlanetSettings, normal: Vec3) -> Visibility {
+    if settings.shape != BaseShape::Planet && normal != Vec3::Y {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    }
+}
+
+/// Returns how many triangles the selected base shape has at the given resolution.
+pub fn triangle_count(shape: BaseShape, resolution: u32) -> u64 {
+    let resolution = u64::from(resolution.max(MIN_RESOLUTION));
+    match shape {
+        BaseShape::Planet => FACE_DIRECTIONS.len() as u64 * (resolution - 1).pow(2) * 2,
+        BaseShape::Plane => (resolution - 1).pow(2) * 2,
+        // Each of the `2 * resolution` sectors has a single triangle in both polar stacks.
+        BaseShape::UvSphere => 2 * resolution * (resolution - 1) * 2,
+        BaseShape::Icosphere => 20 * (resolution + 1).pow(2),
+    }
+}
+
+/// Returns how many vertices the selected base shape has at the given resolution; flat
+/// shading gives each of the planet's triangles its own three.
+pub fn vertex_count(shape: BaseShape, resolution: u32, normal_shading: NormalShading) -> u64 {
+    let stacks = u64::from(resolution.max(MIN_RESOLUTION));
+    match shape {
+        BaseShape::Planet | BaseShape::Icosphere if normal_shading == NormalShading::Flat => {
+            triangle_count(shape, resolution) * 3
+        }
+        BaseShape::Planet => FACE_DIRECTIONS.len() as u64 * stacks.pow(2),
+        BaseShape::Plane => stacks.pow(2),
+        // The seam and pole vertices are duplicated, one per stack and sector boundary.
+        BaseShape::UvSphere => (stacks + 1) * (2 * stacks + 1),
+        BaseShape::Icosphere => 10 * (stacks + 1).pow(2) + 2,
+    }
+}
+
+/// Generates the vertices and indices for a single face of the cube/sphere,
+/// raised by `height` along each point's direction, scaled to `radius` and squashed along
+/// the Y axis by `flattening`.
+///
+/// `height` is sampled at unit directions, so faces meet without seams. The normals of an
+/// undisplaced face follow the smooth surface; once any vertex is displaced they are
+/// recomputed from the triangles with [`recompute_smooth_normals`]. With
+/// [`NormalShading::Flat`], every triangle gets its own vertices and face normal instead.
+///
+/// `resolution` counts the vertices along each edge and must lie within [`MIN_RESOLUTION`]
+/// and [`MAX_RESOLUTION`], and `normal` must be a unit vector; otherwise a [`MeshGenError`]
+/// is returned.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy::render::mesh::VertexAttributeValues;
+/// use bevy_mesh::generation::{MIN_RESOLUTION, NormalShading, create_face_mesh};
+///
+/// let shading = NormalShading::Smooth;
+/// let mesh = create_face_mesh(MIN_RESOLUTION, Vec3::Y, true, 0.0, 1.0, shading, |_| 0.0)?;
+/// let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+/// else {
+///     panic!("the face has positions");
+/// };
+/// assert!(positions.iter().flatten().all(|c| c.is_finite()));
+/// # Ok::<(), bevy_mesh::generation::MeshGenError>(())
+/// ```
+pub fn create_face_mesh(
+    resolution: u32,
+    normal: Vec3,
+    spherify: bool,
+    flattening: f32,
+    radius: f32,
+    shading: NormalShading,
+    height: impl Fn(Vec3) -> f32,
+) -> Result<Mesh, MeshGenError> {
+    if !normal.is_normalized() {
+        return Err(MeshGenError::NormalNotUnit(normal));
+    }
+    let (axis_a, axis_b) = face_axes(normal);
+    let scale = polar_scale(flattening) * radius;
+    let base_point = |percent: Vec2| {
+        let point_on_unit_cube =
+            normal + (percent.x - 0.5) * 2.0 * axis_a + (percent.y - 0.5) * 2.0 * axis_b;
+        if spherify {
+            point_on_unit_cube.normalize()
+        } else {
+            point_on_unit_cube
+        }
+    };
+    let displaced = Cell::new(false);
+    let mut mesh = create_grid_mesh(resolution, |percent| {
+        let point = base_point(percent);
+        let height = height(point.normalize());
+        let position = point * (1.0 + height) * scale;
+        if height != 0.0 {
+            // Replaced below, once all the triangles are in place.
+            displaced.set(true);
+            return (position, normal);
+        }
+        // The normal comes from the undisplaced surface; `axis_a × axis_b` points out.
+        let du = base_point(percent + Vec2::X * NORMAL_STEP)
+            - base_point(percent - Vec2::X * NORMAL_STEP);
+        let dv = base_point(percent + Vec2::Y * NORMAL_STEP)
+            - base_point(percent - Vec2::Y * NORMAL_STEP);
+        (
+            position,
+            (du * scale).cross(dv * scale).normalize_or(normal),
+        )
+    })?;
+    match shading {
+        NormalShading::Smooth if displaced.get() => recompute_smooth_normals(&mut mesh),
+        NormalShading::Smooth => {}
+        NormalShading::Flat => apply_flat_shading(&mut mesh),
+    }
+    Ok(mesh)
+}
+
+/// Gives every triangle its own three vertices, lit by the triangle's face normal, and
+/// indexes them in order.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy::render::mesh::Indices;
+/// use bevy_mesh::generation::{NormalShading, create_face_mesh};
+///
+/// let smooth = create_face_mesh(8, Vec3::Y, true, 0.0, 1.0, NormalShading::Smooth, |_| 0.0)?;
+/// let flat = create_face_mesh(8, Vec3::Y, true, 0.0, 1.0, NormalShading::Flat, |_| 0.0)?;
+/// let triangles = smooth.indices().map_or(0, |indices| indices.len() / 3);
+/// assert_eq!(flat.count_vertices(), triangles * 3);
+/// let Some(Indices::U32(indices)) = flat.indices() else {
+///     panic!("the face is indexed");
+/// };
+/// assert!(indices.iter().enumerate().all(|(i, &index)| index as usize == i));
+/// # Ok::<(), bevy_mesh::generation::MeshGenError>(())
+/// ```
+pub fn apply_flat_shading(mesh: &mut Mesh) {
+    mesh.duplicate_vertices();
+    mesh.compute_flat_normals();
+    let vertex_count = mesh.count_vertices() as u32;
+    mesh.insert_indices(Indices::U32((0..vertex_count).collect()));
+}
+
+/// Replaces the mesh's normals with the sum of the normals of the triangles around each
+/// vertex, normalized. Larger triangles weigh more, since their normals are summed unnormalized.
+///
+/// Vertices on the border of an open mesh only see the triangles inside it, so the faces of a
+/// planet are welded along their seams afterwards by [`weld_seam_normals`].
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy::render::mesh::VertexAttributeValues;
+/// use bevy_mesh::generation::{NormalShading, create_face_mesh, recompute_smooth_normals};
+///
+/// // The cosine of the largest angle each normal makes with the radial direction.
+/// let min_radial_cos = |mesh: &Mesh| {
+///     let (
+///         Some(VertexAttributeValues::Float32x3(positions)),
+///         Some(VertexAttributeValues::Float32x3(normals)),
+///     ) = (
+///         mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+///         mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+///     )
+///     else {
+///         panic!("the face has positions and normals");
+///     };
+///     positions
+///         .iter()
+///         .zip(normals)
+///         .map(|(&p, &n)| Vec3::from(p).normalize().dot(Vec3::from(n)))
+///         .fold(1.0, f32::min)
+/// };
+///
+/// let smooth = NormalShading::Smooth;
+/// let mut sphere = create_face_mesh(16, Vec3::Y, true, 0.0, 1.0, smooth, |_| 0.0)?;
+/// recompute_smooth_normals(&mut sphere);
+/// assert!(min_radial_cos(&sphere) > 0.99);
+///
+/// let bumpy = create_face_mesh(16, Vec3::Y, true, 0.0, 1.0, smooth, |d| {
+///     0.1 * (8.0 * d.x).sin()
+/// })?;
+/// assert!(min_radial_cos(&bumpy) < 0.95);
+/// # Ok::<(), bevy_mesh::generation::MeshGenError>(())
+/// ```
+pub fn recompute_smooth_normals(mesh: &mut Mesh) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        return;
+    };
+    let mut sums = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize);
+        let (Some(&pa), Some(&pb), Some(&pc)) =
+            (positions.get(a), positions.get(b), positions.get(c))
+        else {
+            continue;
+        };
+        let (pa, pb, pc) = (Vec3::from(pa), Vec3::from(pb), Vec3::from(pc));
+        let face_normal = (pb - pa).cross(pc - pa);
+        sums[a] += face_normal;
+        sums[b] += face_normal;
+        sums[c] += face_normal;
+    }
+    let normals: Vec<[f32; 3]> = sums
+        .into_iter()
+        .map(|sum| sum.normalize_or_zero().into())
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+}
+
+/// Returns the per-axis scale that flattens the planet at the poles; a `flattening`
+/// of 0 keeps it round, while Earth's is about 0.0034.
+pub(crate) fn polar_scale(flattening: f32) -> Vec3 {
+    Vec3::new(1.0, 1.0 - flattening, 1.0)
+}
+
+/// Generates an icosphere of the given `radius`, raised by `height` along each vertex's
+/// direction and squashed along the Y axis by `flattening`, like [`create_face_mesh`] but as
+/// one seamless mesh.
+///
+/// Each edge of the icosahedron is split `subdivisions` times, which must lie within
+/// [`MIN_RESOLUTION`] and [`MAX_ICOSPHERE_SUBDIVISIONS`].
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_mesh::generation::{BaseShape, NormalShading, create_icosphere_mesh, triangle_count};
+///
+/// let mesh = create_icosphere_mesh(8, 0.0, 1.0, NormalShading::Smooth, |_| 0.0)?;
+/// let triangles = mesh.indices().map_or(0, |indices| indices.len() / 3);
+/// assert_eq!(triangles as u64, triangle_count(BaseShape::Icosphere, 8));
+/// # Ok::<(), bevy_mesh::generation::MeshGenError>(())
+/// ```
+pub fn create_icosphere_mesh(
+    subdivisions: u32,
+    flattening: f32,
+    radius: f32,
+    shading: NormalShading,
+    height: impl Fn(Vec3) -> f32,
+) -> Result<Mesh, MeshGenError> {
+    if subdivisions < MIN_RESOLUTION {
+        return Err(MeshGenError::ResolutionTooLow(subdivisions));
+    }
+    let mut mesh = Sphere::new(1.0)
+        .mesh()
+        .ico(subdivisions)
+        .map_err(|_| MeshGenError::ResolutionTooHigh(subdivisions))?;
+    let scale = polar_scale(flattening) * radius;
+    let mut displaced = false;
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions.iter_mut() {
+            let direction = Vec3::from(*position).normalize();
+            let height = height(direction);
+            displaced |= height != 0.0;
+            *position = (direction * (1.0 + height) * scale).into();
+        }
+    }
+    // Bevy's normals point straight out, which only holds on the undisplaced, round sphere.
+    match shading {
+        NormalShading::Smooth if displaced || flattening != 0.0 => {
+            recompute_smooth_normals(&mut mesh);
+        }
+        NormalShading::Smooth => {}
+        NormalShading::Flat => apply_flat_shading(&mut mesh),
+    }
+    Ok(mesh)
+}
+
+/// Replaces the mesh's UVs with the longitude and latitude of each vertex.
+///
+/// Triangles straddling the date line at -Z interpolate across the whole texture, since
+/// the face meshes don't duplicate vertices along a seam.
+fn apply_equirectangular_uvs(mesh: &mut Mesh) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|&position| {
+            let direction = Vec3::from(position).normalize_or_zero();
+            let longitude = direction.x.atan2(direction.z);
+            let latitude = direction.y.clamp(-1.0, 1.0).asin();
+            [0.5 + longitude / TAU, 0.5 - latitude / PI]
+        })
+        .collect();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+}
+
+/// Generates a UV sphere of the given `radius` from Bevy's `Sphere` primitive, with twice as
+/// many sectors as stacks so its quads are roughly square at the equator.
+pub fn create_uv_sphere_mesh(stacks: u32, radius: f32) -> Result<Mesh, MeshGenError> {
+    let stacks_64 = u64::from(stacks);
+    check_resolution(
+        stacks,
+        (stacks_64 + 1).saturating_mul(stacks_64 * 2 + 1),
+        (stacks_64 * stacks_64).saturating_mul(12),
+    )?;
+    Ok(Sphere::new(radius).mesh().uv(stacks * 2, stacks))
+}
+
+/// Generates a flat, upward-facing square grid of side `size` centered on the origin.
+pub fn create_plane_mesh(resolution: u32, size: f32) -> Result<Mesh, MeshGenError> {
+    let (axis_a, axis_b) = face_axes(Vec3::Y);
+    create_grid_mesh(resolution, |percent| {
+        let point = ((percent.x - 0.5) * axis_a + (percent.y - 0.5) * axis_b) * size;
+        (point, Vec3::Y)
+    })
+}
+
+/// Generates a `resolution` x `resolution` grid of triangles, placing each vertex
+/// and its normal from its position across the grid in `[0, 1]`, which is also its UV.
+fn create_grid_mesh(
+    resolution: u32,
+    vertex: impl Fn(Vec2) -> (Vec3, Vec3),
+) -> Result<Mesh, MeshGenError> {
+    let (vertex_count, index_count) = grid_counts(resolution);
+    check_resolution(resolution, vertex_count, index_count)?;
+    let num_vertices = vertex_count as usize;
+    let num_indices = index_count as usize;
+
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(num_vertices);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(num_vertices);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(num_vertices);
+    let mut indices = Vec::with_capacity(num_indices);
+
+    for y in 0..resolution {
+        for x in 0..resolution {
+            let i = x + y * resolution;
+            let percent = Vec2::new(x as f32, y as f32) / (resolution - 1) as f32;
+
+            let (position, vertex_normal) = vertex(percent);
+            positions.push(position.into());
+            normals.push(vertex_normal.into());
+            uvs.push(percent.into());
+
+            if x != resolution - 1 && y != resolution - 1 {
+                indices.push(i);
+                indices.push(i + resolution + 1);
+                indices.push(i + resolution);
+
+                indices.push(i);
+                indices.push(i + 1);
+                indices.push(i + resolution + 1);
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    Ok(mesh)
+}
+
+/// Returns how many vertices and indices a `resolution` x `resolution` grid has, counted
+/// wide enough not to overflow for any resolution.
+fn grid_counts(resolution: u32) -> (u64, u64) {
+    let resolution = u64::from(resolution);
+    (
+        resolution * resolution,
+        resolution.saturating_sub(1).pow(2).saturating_mul(6),
+    )
+}
+
+/// Checks that `resolution` forms triangles and that the `vertex_count` and `index_count` it
+/// yields can be counted in 32 bits.
+fn check_resolution(
+    resolution: u32,
+    vertex_count: u64,
+    index_count: u64,
+) -> Result<(), MeshGenError> {
+    let limit = u64::from(u32::MAX);
+    if resolution < MIN_RESOLUTION {
+        Err(MeshGenError::ResolutionTooLow(resolution))
+    } else if resolution > MAX_RESOLUTION || vertex_count > limit || index_count > limit {
+        Err(MeshGenError::ResolutionTooHigh(resolution))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the two tangent axes spanning the cube face with the given normal.
+pub(crate) fn face_axes(normal: Vec3) -> (Vec3, Vec3) {
+    let axis_a = Vec3::new(normal.y, normal.z, normal.x);
+    (axis_a, normal.cross(axis_a))
+}
+
+/// Returns which of the [`FACE_DIRECTIONS`] the given direction maps onto, i.e. its dominant
+/// axis, so a point on the planet can be traced back to the face mesh holding it.
+///
+/// Directions on a cube edge or corner, where axes tie, map onto X before Y before Z; the zero
+/// vector maps onto +X.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_mesh::generation::face_for_direction;
+///
+/// assert_eq!(face_for_direction(Vec3::new(0.2, -0.9, 0.4)), Vec3::NEG_Y);
+/// assert_eq!(face_for_direction(Vec3::new(-1.0, 0.0, 1.0)), Vec3::NEG_X);
+/// ```
+pub fn face_for_direction(direction: Vec3) -> Vec3 {
+    let abs = direction.abs();
+    let max = abs.max_element();
+    if abs.x == max {
+        Vec3::X * direction.x.signum()
+    } else if abs.y == max {
+        Vec3::Y * direction.y.signum()
+    } else {
+        Vec3::Z * direction.z.signum()
+    }
+}
+
+/// Returns the point on the planet surface in the given direction and the surface normal there.
+///
+/// For the flat plane, the point is `direction` dropped straight onto the plane and kept within
+/// its edges, so callers should pass a point rather than a unit vector.
+pub fn surface_point(direction: Vec3, settings: &PlanetSettings) -> (Vec3, Vec3) {
+    match settings.shape {
+        BaseShape::Plane => {
+            let half_size = 0.5 * settings.plane_size * settings.radius;
+            let point = Vec3::new(direction.x, 0.0, direction.z)
+                .clamp(Vec3::splat(-half_size), Vec3::splat(half_size));
+            return (point, Vec3::Y);
+        }
+        BaseShape::UvSphere => {
+            let direction = direction.normalize();
+            return (direction * settings.radius, direction);
+        }
+        BaseShape::Planet | BaseShape::Icosphere => {}
+    }
+    // Work on the round planet, then squash the result like the face meshes.
+    let scale = polar_scale(settings.flattening);
+    let direction = (direction / scale).normalize();
+    let (point, normal) = if settings.spherify || settings.shape == BaseShape::Icosphere {
+        (direction, direction)
+    } else {
+        let normal = face_for_direction(direction);
+        (direction / direction.dot(normal), normal)
+    };
+    let height =
+        (1.0 + settings.noise_height(direction)) * (1.0 + settings.sculpt.height(direction));
+    (
+        point * scale * height * settings.radius,
+        (normal / scale).normalize(),
+    )
+}
+
+/// Averages the normals of vertices that lie within `tolerance` of each other across the
+/// meshes, so lighting is continuous over the seams between separately generated faces.
+pub fn weld_seam_normals(meshes: &mut [Mesh], tolerance: f32) {
+    if !(tolerance.is_finite() && tolerance > 0.0) {
+        return;
+    }
+    // Every vertex with a normal, as its mesh, its index there, its position and its normal.
+    let mut vertices = Vec::new();
+    for (mesh_index, mesh) in meshes.iter().enumerate() {
+        let (
+            Some(VertexAttributeValues::Float32x3(positions)),
+            Some(VertexAttributeValues::Float32x3(normals)),
+        ) = (
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+        )
+        else {
+            continue;
+        };
+        for (index, (&position, &normal)) in positions.iter().zip(normals).enumerate() {
+            vertices.push((mesh_index, index, Vec3::from(position), Vec3::from(normal)));
+        }
+    }
+    let points: Vec<Vec3> = vertices
+        .iter()
+        .map(|&(_, _, position, _)| position)
+        .collect();
+    let groups = weld_groups(&points, tolerance);
+    let mut sums: HashMap<usize, (Vec3, u32)> = HashMap::new();
+    for (&group, &(_, _, _, normal)) in groups.iter().zip(&vertices) {
+        let (sum, count) = sums.entry(group).or_default();
+        *sum += normal;
+        *count += 1;
+    }
+
+    for (&group, &(mesh_index, index, _, _)) in groups.iter().zip(&vertices) {
+        let Some(welded) = sums
+            .get(&group)
+            .filter(|&&(_, count)| count > 1)
+            .and_then(|(sum, _)| sum.try_normalize())
+        else {
+            continue;
+        };
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            meshes[mesh_index].attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        {
+            normals[index] = welded.into();
+        }
+    }
+}
+
+/// Groups the points that lie within `tolerance` of each other, or are linked by a chain of
+/// such points, and returns each point's group as the index of the group's first point.
+///
+/// Points are binned into cells as wide as the tolerance and compared with those in the
+/// neighboring cells too, so a pair straddling a cell boundary is still found.
+pub(crate) fn weld_groups(points: &[Vec3], tolerance: f32) -> Vec<usize> {
+    let mut parents: Vec<usize> = (0..points.len()).collect();
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    }
+    if !(tolerance.is_finite() && tolerance > 0.0) {
+        return parents;
+    }
+    let cell = |point: Vec3| (point / tolerance).floor().as_ivec3();
+    let mut cells: HashMap<IVec3, Vec<usize>> = HashMap::new();
+    for (index, &point) in points.iter().enumerate() {
+        let home = cell(point);
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let Some(neighbors) = cells.get(&(home + IVec3::new(x, y, z))) else {
+                        continue;
+                    };
+                    for &other in neighbors {
+                        if point.distance(points[other]) <= tolerance {
+                            let (a, b) = (root(&mut parents, index), root(&mut parents, other));
+                            // The smaller index stays the root, so it is the group's first.
+                            parents[a.max(b)] = a.min(b);
+                        }
+                    }
+                }
+            }
+        }
+        cells.entry(home).or_default().push(index);
+    }
+    (0..points.len())
+        .map(|index| root(&mut parents, index))
+        .collect()
+}
+
+/// Builds the planet's face meshes without the editor, starting from the default settings.
+///
+/// ```
+/// use bevy_mesh::generation::{NoiseLayer, PlanetMeshBuilder};
+///
+/// let faces = PlanetMeshBuilder::new()
+///     .resolution(16)
+///     .spherify(true)
+///     .seed(42)
+///     .noise_layers(vec![NoiseLayer::default()])
+///     .build()
+///     .expect("the settings are valid");
+/// assert_eq!(faces.len(), 6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PlanetMeshBuilder {
+    settings: PlanetSettings,
+}
+
+impl PlanetMeshBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from existing settings, such as ones saved by the editor.
+    pub fn from_settings(settings: PlanetSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Sets the number of vertices along each edge of a face; at least [`MIN_RESOLUTION`].
+    pub fn resolution(mut self, resolution: u32) -> Self {
+        self.settings.resolution = resolution;
+        self
+    }
+
+    pub fn shape(mut self, shape: BaseShape) -> Self {
+        self.settings.shape = shape;
+        self
+    }
+
+    pub fn spherify(mut self, spherify: bool) -> Self {
+        self.settings.spherify = spherify;
+        self
+    }
+
+    pub fn normal_shading(mut self, normal_shading: NormalShading) -> Self {
+        self.settings.normal_shading = normal_shading;
+        self
+    }
+
+    /// Squashes the planet along the Y axis; 0 keeps it round and it must stay below 1.
+    pub fn flattening(mut self, flattening: f32) -> Self {
+        self.settings.flattening = flattening;
+        self
+    }
+
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.settings.radius = radius;
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.settings.seed = seed;
+        self
+    }
+
+    pub fn variation(mut self, variation: f32) -> Self {
+        self.settings.variation = variation;
+        self
+    }
+
+    pub fn noise_layers(mut self, layers: Vec<NoiseLayer>) -> Self {
+        self.settings.noise_layers = layers;
+        self
+    }
+
+    pub fn erosion(mut self, erosion: Erosion) -> Self {
+        self.settings.erosion = erosion;
+        self
+    }
+
+    pub fn lateral_strength(mut self, strength: f32) -> Self {
+        self.settings.lateral_strength = strength;
+        self
+    }
+
+    pub fn uv_projection(mut self, projection: UvProjection) -> Self {
+        self.settings.uv_projection = projection;
+        self
+    }
+
+    /// Sets the side length of the flat plane shape.
+    pub fn plane_size(mut self, size: f32) -> Self {
+        self.settings.plane_size = size;
+        self
+    }
+
+    pub fn settings(&self) -> &PlanetSettings {
+        &self.settings
+    }
+
+    /// Generates the mesh of every face shown for the settings, with the normals along the
+    /// seams of a spherified planet welded, or the error for the first invalid setting.
+    ///
+    /// A planet has six faces; the flat plane and the UV sphere are a single mesh.
+    pub fn build(&self) -> Result<Vec<Mesh>, MeshGenError> {
+        self.validate()?;
+        let settings = &self.settings;
+        let mut meshes = FACE_DIRECTIONS
+            .into_iter()
+            .filter(|&normal| face_visibility(settings, normal) != Visibility::Hidden)
+            .map(|normal| surface_mesh(settings, normal))
+            .collect::<Result<Vec<_>, _>>()?;
+        if settings.welds_seams() {
+            weld_seam_normals(&mut meshes, settings.seam_weld_tolerance());
+        }
+        Ok(meshes)
+    }
+
+    fn validate(&self) -> Result<(), MeshGenError> {
+        let settings = &self.settings;
+        if !(0.0..1.0).contains(&settings.flattening) {
+            return Err(MeshGenError::FlatteningOutOfRange(settings.flattening));
+        }
+        if !(settings.radius.is_finite() && settings.radius > 0.0) {
+            return Err(MeshGenError::RadiusNotPositive(settings.radius));
+        }
+        if !(settings.plane_size.is_finite() && settings.plane_size > 0.0) {
+            return Err(MeshGenError::PlaneSizeNotPositive(settings.plane_size));
+        }
+        if !settings.erosion.strength.is_finite() {
+            return Err(MeshGenError::ErosionStrengthNotFinite(
+                settings.erosion.strength,
+            ));
+        }
+        if !(0.0..=1.0).contains(&settings.variation) {
+            return Err(MeshGenError::VariationOutOfRange(settings.variation));
+        }
+        if !settings.lateral_strength.is_finite() {
+            return Err(MeshGenError::LateralStrengthNotFinite(
+                settings.lateral_strength,
+            ));
+        }
+        for (index, layer) in settings.noise_layers.iter().enumerate() {
+            let finite = [
+                layer.frequency,
+                layer.amplitude,
+                layer.lacunarity,
+                layer.persistence,
+                layer.ridge_offset,
+                layer.ridge_gain,
+            ]
+            .iter()
+            .all(|value| value.is_finite());
+            if !finite {
+                return Err(MeshGenError::NoiseLayerNotFinite(index));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn positions(mesh: &Mesh) -> Vec<[f32; 3]> {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+            _ => panic!("the mesh has positions"),
+        }
+    }
+
+    fn normals(mesh: &Mesh) -> Vec<[f32; 3]> {
+        match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => normals.clone(),
+            _ => panic!("the mesh has normals"),
+        }
+    }
+
+    /// Returns the bits of every position and every index, to compare meshes byte for byte.
+    fn mesh_bits(meshes: &[Mesh]) -> Vec<(Vec<[u32; 3]>, Vec<usize>)> {
+        meshes
+            .iter()
+            .map(|mesh| {
+                let positions = positions(mesh)
+                    .into_iter()
+                    .map(|position| position.map(f32::to_bits))
+                    .collect();
+                let indices = mesh.indices().map(|indices| indices.iter().collect());
+                (positions, indices.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solo_and_mute_choose_the_summed_layers() {
+        let hills = NoiseLayer::default();
+        let ridges = NoiseLayer {
+            mode: NoiseMode::Ridged,
+            frequency: 5.0,
+            ..default()
+        };
+        let direction = Vec3::new(0.3, 0.8, -0.5).normalize();
+        let height = |layers: Vec<NoiseLayer>| {
+            PlanetSettings {
+                noise_layers: layers,
+                ..default()
+            }
+            .noise_height(direction)
+        };
+        let only_hills = height(vec![hills.clone()]);
+        let only_ridges = height(vec![ridges.clone()]);
+        assert_ne!(only_hills, only_ridges);
+
+        let solo = |layer: &NoiseLayer| NoiseLayer {
+            solo: true,
+            ..layer.clone()
+        };
+        let mute = |layer: &NoiseLayer| NoiseLayer {
+            mute: true,
+            ..layer.clone()
+        };
+        assert_eq!(height(vec![solo(&hills), ridges.clone()]), only_hills);
+        assert_eq!(height(vec![mute(&hills), ridges.clone()]), only_ridges);
+        // Soloing wins over muting, and a disabled layer stays out even when soloed.
+        assert_eq!(
+            height(vec![hills.clone(), mute(&solo(&ridges))]),
+            only_ridges
+        );
+        let disabled = NoiseLayer {
+            enabled: false,
+            ..solo(&ridges)
+        };
+        assert_eq!(height(vec![hills.clone(), disabled]), only_hills);
+        assert_eq!(height(vec![mute(&hills), mute(&ridges)]), 0.0);
+    }
+
+    #[test]
+    fn resolutions_below_the_minimum_are_rejected() {
+        for resolution in [0, 1] {
+            assert_eq!(
+                check_resolution(resolution, 0, 0),
+                Err(MeshGenError::ResolutionTooLow(resolution))
+            );
+            let mesh = create_face_mesh(
+                resolution,
+                Vec3::Y,
+                true,
+                0.0,
+                1.0,
+                NormalShading::Smooth,
+                |_| 0.0,
+            );
+            assert_eq!(mesh.err(), Some(MeshGenError::ResolutionTooLow(resolution)));
+        }
+        assert_eq!(check_resolution(MIN_RESOLUTION, 4, 6), Ok(()));
+    }
+
+    /// Catches order-of-magnitude slowdowns in face generation; run with `--ignored`.
+    ///
+    /// Baseline: about 0.65 s for the six faces with two noise layers in a debug build, so a
+    /// ten second threshold leaves plenty of room for slower machines.
+    #[test]
+    #[ignore = "timing-based"]
+    fn generating_at_resolution_128_stays_fast() {
+        let settings = PlanetSettings {
+            resolution: 128,
+            noise_layers: vec![NoiseLayer::default(), NoiseLayer::default()],
+            ..default()
+        };
+        let start = std::time::Instant::now();
+        for normal in FACE_DIRECTIONS {
+            surface_mesh(&settings, normal).unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed < std::time::Duration::from_secs(10),
+            "generating took {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn lateral_displacement_moves_vertices_along_the_surface() {
+        let radial = PlanetSettings {
+            resolution: 12,
+            noise_layers: vec![NoiseLayer {
+                amplitude: 0.2,
+                ..default()
+            }],
+            ..default()
+        };
+        let lateral = PlanetSettings {
+            lateral_strength: 1.0,
+            ..radial.clone()
+        };
+        let before = surface_mesh(&radial, Vec3::X).unwrap();
+        let after = surface_mesh(&lateral, Vec3::X).unwrap();
+
+        let mut moved = false;
+        for (&before, &after) in positions(&before).iter().zip(&positions(&after)) {
+            let (before, after) = (Vec3::from(before), Vec3::from(after));
+            moved |= before.distance(after) > 1e-4;
+            assert!((after - before).dot(before.normalize()).abs() < 1e-5);
+        }
+        assert!(moved);
+
+        let mut recomputed = after.clone();
+        recompute_smooth_normals(&mut recomputed);
+        assert_eq!(normals(&after), normals(&recomputed));
+        assert_ne!(normals(&after), normals(&before));
+    }
+
+    #[test]
+    fn generation_is_identical_serially_and_in_parallel() {
+        let layers = vec![
+            NoiseLayer::default(),
+            NoiseLayer {
+                mode: NoiseMode::Ridged,
+                frequency: 4.0,
+                amplitude: 0.05,
+                ..default()
+            },
+        ];
+        let configs = [
+            PlanetMeshBuilder::new()
+                .resolution(16)
+                .seed(3)
+                .noise_layers(layers.clone()),
+            PlanetMeshBuilder::new()
+                .resolution(9)
+                .seed(11)
+                .noise_layers(layers.clone())
+                .normal_shading(NormalShading::Flat),
+            PlanetMeshBuilder::new()
+                .resolution(12)
+                .noise_layers(layers.clone())
+                .lateral_strength(0.5),
+            PlanetMeshBuilder::new()
+                .resolution(4)
+                .shape(BaseShape::Icosphere)
+                .noise_layers(layers),
+        ];
+        for builder in configs {
+            let serial = builder.build().unwrap();
+            assert_eq!(mesh_bits(&serial), mesh_bits(&builder.build().unwrap()));
+
+            // Like the app, generate each face on its own thread and weld them afterwards.
+            let settings = &builder.settings;
+            let mut parallel: Vec<Mesh> = std::thread::scope(|scope| {
+                let tasks: Vec<_> = FACE_DIRECTIONS
+                    .into_iter()
+                    .filter(|&normal| face_visibility(settings, normal) != Visibility::Hidden)
+                    .map(|normal| scope.spawn(move || surface_mesh(settings, normal).unwrap()))
+                    .collect();
+                tasks.into_iter().map(|task| task.join().unwrap()).collect()
+            });
+            if settings.welds_seams() {
+                weld_seam_normals(&mut parallel, settings.seam_weld_tolerance());
+            }
+            assert_eq!(mesh_bits(&serial), mesh_bits(&parallel));
+        }
+    }
+
+    #[test]
+    fn ice_caps_follow_the_seed_and_relight_the_raised_sheet() {
+        let ice_caps = IceCaps {
+            enabled: true,
+            latitude: 40.0,
+            boundary_noise: 15.0,
+            thickness: 0.05,
+            ..default()
+        };
+        let edge = Vec3::new(1.0, 0.84, 0.0).normalize();
+        let coverage: Vec<f32> = (0..8).map(|seed| ice_caps.coverage(edge, seed)).collect();
+        assert!(coverage.iter().any(|&c| c != coverage[0]));
+        assert_eq!(coverage[3], ice_caps.coverage(edge, 3));
+
+        let settings = PlanetSettings {
+            resolution: 12,
+            seed: 5,
+            ice_caps,
+            ..default()
+        };
+        let mesh = surface_mesh(&settings, Vec3::Z).unwrap();
+        let mut recomputed = mesh.clone();
+        recompute_smooth_normals(&mut recomputed);
+        assert_eq!(normals(&mesh), normals(&recomputed));
+    }
+
+    #[test]
+    fn non_finite_vertices_are_repaired() {
+        let radius = 40.0;
+        let mut mesh =
+            create_face_mesh(3, Vec3::Y, true, 0.0, radius, NormalShading::Smooth, |_| {
+                0.0
+            })
+            .unwrap();
+        let original = positions(&mesh);
+        let mut broken = original.clone();
+        broken[0] = [f32::NAN, 0.0, 0.0];
+        broken[4] = [f32::INFINITY, 1.0, f32::NEG_INFINITY];
+        broken[8] = [f32::NAN; 3];
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, broken);
+        let mut broken = normals(&mesh);
+        broken[4] = [f32::NAN; 3];
+        broken[7] = [0.0, f32::INFINITY, 0.0];
+        broken[8] = [f32::NAN; 3];
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, broken);
+
+        repair_non_finite_vertices(&mut mesh, radius, Vec3::Y);
+        let (positions, normals) = (positions(&mesh), normals(&mesh));
+        assert!(positions.iter().flatten().all(|c| c.is_finite()));
+        assert!(normals.iter().flatten().all(|c| c.is_finite()));
+        // Repaired points lie on the surface near where they were, rather than collapsing
+        // together or sinking to the unit sphere.
+        for index in [0, 4, 8] {
+            let point = Vec3::from(positions[index]);
+            assert!((point.length() - radius).abs() < 1e-3);
+            let expected = Vec3::from(original[index]).normalize();
+            assert!(point.normalize().dot(expected) > 0.9, "vertex {index}");
+        }
+        assert!(Vec3::from(normals[7]).is_normalized());
+    }
+
+    #[test]
+    fn pathological_noise_yields_a_finite_mesh() {
+        let settings = PlanetSettings {
+            resolution: 8,
+            noise_layers: vec![NoiseLayer {
+                amplitude: f32::INFINITY,
+                ..default()
+            }],
+            ..default()
+        };
+        for normal in FACE_DIRECTIONS {
+            let mesh = surface_mesh(&settings, normal).unwrap();
+            assert!(positions(&mesh).iter().flatten().all(|c| c.is_finite()));
+            assert!(normals(&mesh).iter().flatten().all(|c| c.is_finite()));
+        }
+    }
+
+    #[test]
+    fn ties_on_edges_and_corners_pick_x_then_y_then_z() {
+        let cases = [
+            (Vec3::new(1.0, 1.0, 0.0), Vec3::X),
+            (Vec3::new(-1.0, 1.0, 0.0), Vec3::NEG_X),
+            (Vec3::new(0.0, -1.0, 1.0), Vec3::NEG_Y),
+            (Vec3::new(0.0, 1.0, -1.0), Vec3::Y),
+            (Vec3::new(1.0, 0.0, -1.0), Vec3::X),
+            (Vec3::new(-1.0, -1.0, -1.0), Vec3::NEG_X),
+            (Vec3::new(1.0, -1.0, 1.0), Vec3::X),
+            (Vec3::ZERO, Vec3::X),
+        ];
+        for (direction, face) in cases {
+            assert_eq!(face_for_direction(direction), face, "{direction}");
+            assert_eq!(
+                face_for_direction(direction.normalize_or_zero() * 7.0),
+                face
+            );
+        }
+        // Just off an edge, the larger axis wins.
+        let off_edge = Vec3::new(1.0, 1.0 + 1e-6, 0.0);
+        assert_eq!(face_for_direction(off_edge), Vec3::Y);
+        for normal in FACE_DIRECTIONS {
+            assert_eq!(face_for_direction(normal), normal);
+        }
+    }
+
+    #[test]
+    fn points_straddling_a_cell_boundary_are_welded() {
+        let points = [
+            Vec3::new(0.099_999, 0.0, 0.0),
+            Vec3::new(0.100_001, 0.0, 0.0),
+            Vec3::new(0.5, 0.0, 0.0),
+            Vec3::new(0.5, 0.099_999, -0.000_001),
+            Vec3::new(-0.3, 0.2, 0.0),
+        ];
+        assert_eq!(weld_groups(&points, 0.001), [0, 0, 2, 3, 4]);
+        // With a wider tolerance, the chain through the middle point joins the first three.
+        let chained = [Vec3::ZERO, Vec3::X * 0.08, Vec3::X * 0.16, Vec3::Y];
+        assert_eq!(weld_groups(&chained, 0.1), [0, 0, 0, 3]);
+        assert_eq!(weld_groups(&chained, 0.0), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn seam_normals_match_across_faces() {
+        for radius in [1.0, 1000.0] {
+            let faces = PlanetMeshBuilder::new()
+                .resolution(9)
+                .radius(radius)
+                .noise_layers(vec![NoiseLayer {
+                    amplitude: 0.1,
+                    ..default()
+                }])
+                .build()
+                .unwrap();
+            let vertices: Vec<(usize, Vec3, Vec3)> = faces
+                .iter()
+                .enumerate()
+                .flat_map(|(face, mesh)| {
+                    let normals = normals(mesh);
+                    positions(mesh)
+                        .into_iter()
+                        .zip(normals)
+                        .map(move |(p, n)| (face, Vec3::from(p), Vec3::from(n)))
+                })
+                .collect();
+            let mut seam_pairs = 0;
+            for (i, &(face, position, normal)) in vertices.iter().enumerate() {
+                for &(other_face, other_position, other_normal) in &vertices[i + 1..] {
+                    if face != other_face && position.distance(other_position) < 1e-4 * radius {
+                        seam_pairs += 1;
+                        assert!(
+                            normal.abs_diff_eq(other_normal, 1e-6),
+                            "{normal} differs from {other_normal} at {position}"
+                        );
+                    }
+                }
+            }
+            // Twelve edges of nine vertices, with each corner shared by three faces.
+            assert_eq!(seam_pairs, 12 * 7 + 8 * 3);
+        }
+    }
+
+    #[test]
+    fn the_seed_alone_decides_the_noise() {
+        let seeded = |seed| PlanetSettings {
+            resolution: 8,
+            seed,
+            noise_layers: vec![NoiseLayer::default()],
+            ..default()
+        };
+        let face = |seed| positions(&surface_mesh(&seeded(seed), Vec3::NEG_Z).unwrap());
+        assert_eq!(face(7), face(7));
+        assert_ne!(face(7), face(8));
+
+        let direction = Vec3::new(0.4, -0.2, 0.9).normalize();
+        let heights: Vec<f32> = (0..16)
+            .map(|seed| seeded(seed).noise_height(direction))
+            .collect();
+        assert_eq!(heights[5], seeded(5).noise_height(direction));
+        for (seed, height) in heights.iter().enumerate().skip(1) {
+            assert_ne!(
+                *height, heights[0],
+                "seeds 0 and {seed} give the same noise"
+            );
+        }
+    }
+
+    #[test]
+    fn builder_rejects_each_invalid_setting() {
+        let builder = PlanetMeshBuilder::new().resolution(4);
+        let erosion = |strength| Erosion {
+            strength,
+            ..default()
+        };
+        let broken_layer = NoiseLayer {
+            lacunarity: f32::INFINITY,
+            ..default()
+        };
+        let cases = [
+            (
+                builder.clone().flattening(1.0),
+                MeshGenError::FlatteningOutOfRange(1.0),
+            ),
+            (
+                builder.clone().flattening(-0.1),
+                MeshGenError::FlatteningOutOfRange(-0.1),
+            ),
+            (
+                builder.clone().radius(0.0),
+                MeshGenError::RadiusNotPositive(0.0),
+            ),
+            (
+                builder.clone().plane_size(-2.0),
+                MeshGenError::PlaneSizeNotPositive(-2.0),
+            ),
+            (
+                builder.clone().erosion(erosion(f32::INFINITY)),
+                MeshGenError::ErosionStrengthNotFinite(f32::INFINITY),
+            ),
+            (
+                builder.clone().variation(1.5),
+                MeshGenError::VariationOutOfRange(1.5),
+            ),
+            (
+                builder.clone().lateral_strength(f32::NEG_INFINITY),
+                MeshGenError::LateralStrengthNotFinite(f32::NEG_INFINITY),
+            ),
+            (
+                builder
+                    .clone()
+                    .noise_layers(vec![NoiseLayer::default(), broken_layer]),
+                MeshGenError::NoiseLayerNotFinite(1),
+            ),
+        ];
+        for (builder, error) in cases {
+            assert_eq!(builder.build().err(), Some(error));
+        }
+        // NaN never compares equal, so these are matched by variant.
+        assert!(matches!(
+            builder.clone().radius(f32::NAN).build(),
+            Err(MeshGenError::RadiusNotPositive(radius)) if radius.is_nan()
+        ));
+        assert!(matches!(
+            builder.clone().variation(f32::NAN).build(),
+            Err(MeshGenError::VariationOutOfRange(variation)) if variation.is_nan()
+        ));
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn a_single_vertex_edge_is_an_error_rather_than_nans() {
+        // One vertex per edge would divide by zero when spreading the vertices over the face.
+        let too_low = Some(MeshGenError::ResolutionTooLow(1));
+        let face = create_face_mesh(1, Vec3::Y, true, 0.0, 1.0, NormalShading::Flat, |_| 0.0);
+        assert_eq!(face.err(), too_low);
+        assert_eq!(create_plane_mesh(1, 2.0).err(), too_low);
+        assert_eq!(create_uv_sphere_mesh(1, 1.0).err(), too_low);
+        for shape in [BaseShape::Planet, BaseShape::UvSphere, BaseShape::Plane] {
+            let built = PlanetMeshBuilder::new().resolution(1).shape(shape).build();
+            assert_eq!(built.err(), too_low);
+        }
+    }
+
+    #[test]
+    fn the_largest_resolution_counts_its_indices_in_32_bits() {
+        let check = |resolution| {
+            let (vertices, indices) = grid_counts(resolution);
+            check_resolution(resolution, vertices, indices)
+        };
+        assert_eq!(check(MAX_RESOLUTION), Ok(()));
+        assert!(grid_counts(MAX_RESOLUTION).1 <= u64::from(u32::MAX));
+        // One more vertex along the edge takes the index count past 32 bits.
+        assert!(grid_counts(MAX_RESOLUTION + 1).1 > u64::from(u32::MAX));
+        for resolution in [MAX_RESOLUTION + 1, u32::from(u16::MAX), u32::MAX] {
+            assert_eq!(
+                check(resolution),
+                Err(MeshGenError::ResolutionTooHigh(resolution))
+            );
+        }
+        assert_eq!(
+            create_plane_mesh(u32::MAX, 1.0).err(),
+            Some(MeshGenError::ResolutionTooHigh(u32::MAX))
+        );
+        assert_eq!(
+            create_uv_sphere_mesh(u32::MAX, 1.0).err(),
+            Some(MeshGenError::ResolutionTooHigh(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn every_mesh_error_describes_itself() {
+        let errors = [
+            MeshGenError::ResolutionTooLow(1),
+            MeshGenError::ResolutionTooHigh(MAX_RESOLUTION + 1),
+            MeshGenError::NormalNotUnit(Vec3::ZERO),
+            MeshGenError::FlatteningOutOfRange(1.0),
+            MeshGenError::RadiusNotPositive(0.0),
+            MeshGenError::PlaneSizeNotPositive(0.0),
+            MeshGenError::ErosionStrengthNotFinite(f32::NAN),
+            MeshGenError::VariationOutOfRange(2.0),
+            MeshGenError::LateralStrengthNotFinite(f32::INFINITY),
+            MeshGenError::NoiseLayerNotFinite(3),
+        ];
+        let messages: HashSet<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), errors.len());
+        let normal = create_face_mesh(
+            8,
+            Vec3::new(0.0, 0.5, 0.0),
+            true,
+            0.0,
+            1.0,
+            NormalShading::Smooth,
+            |_| 0.0,
+        );
+        assert_eq!(
+            normal.err(),
+            Some(MeshGenError::NormalNotUnit(Vec3::new(0.0, 0.5, 0.0)))
+        );
+    }
+
+    /// Returns the smallest cosine between each vertex normal and the direction out from the
+    /// center to the vertex.
+    fn min_radial_cos(mesh: &Mesh) -> f32 {
+        positions(mesh)
+            .into_iter()
+            .zip(normals(mesh))
+            .map(|(position, normal)| Vec3::from(position).normalize().dot(Vec3::from(normal)))
+            .fold(1.0, f32::min)
+    }
+
+    #[test]
+    fn recomputed_normals_follow_the_displaced_surface() {
+        let face = |height: fn(Vec3) -> f32| {
+            let mut mesh =
+                create_face_mesh(16, Vec3::Y, true, 0.0, 1.0, NormalShading::Smooth, height)
+                    .unwrap();
+            recompute_smooth_normals(&mut mesh);
+            mesh
+        };
+        let smooth = face(|_| 0.0);
+        assert!(min_radial_cos(&smooth) > 0.99);
+        let bumpy = face(|direction| 0.1 * (8.0 * direction.x).sin());
+        assert!(min_radial_cos(&bumpy) < 0.95);
+        for normal in normals(&bumpy) {
+            assert!((Vec3::from(normal).length() - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn recomputing_normals_survives_degenerate_meshes() {
+        let mesh_of = |positions: Vec<[f32; 3]>, indices: Vec<u32>| {
+            let mut mesh = Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            );
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            mesh.insert_indices(Indices::U32(indices));
+            mesh
+        };
+
+        let mut empty = mesh_of(Vec::new(), Vec::new());
+        recompute_smooth_normals(&mut empty);
+        assert!(normals(&empty).is_empty());
+
+        // A collapsed triangle has no direction to give, and an index past the end is skipped.
+        let mut collapsed = mesh_of(vec![[1.0, 2.0, 3.0]; 3], vec![0, 1, 2, 0, 1, 7]);
+        recompute_smooth_normals(&mut collapsed);
+        assert_eq!(normals(&collapsed), vec![[0.0; 3]; 3]);
+
+        // Without positions or indices there is nothing to recompute from.
+        let mut bare = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        recompute_smooth_normals(&mut bare);
+        assert!(bare.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+        let mut unindexed = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        unindexed.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3]);
+        recompute_smooth_normals(&mut unindexed);
+        assert!(unindexed.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+    }
+
+    #[test]
+    fn surface_points_scale_with_the_radius() {
+        let direction = Vec3::new(1.0, 2.0, -2.0);
+        for radius in [0.5, 40.0] {
+            let sphere = PlanetSettings {
+                shape: BaseShape::UvSphere,
+                radius,
+                ..default()
+            };
+            let (point, normal) = surface_point(direction, &sphere);
+            assert!(point.abs_diff_eq(direction / 3.0 * radius, 1e-4 * radius));
+            assert!(normal.abs_diff_eq(direction / 3.0, 1e-6));
+            let mesh = surface_mesh(&sphere, Vec3::Y).unwrap();
+            for position in positions(&mesh) {
+                assert!((Vec3::from(position).length() - radius).abs() < 1e-4 * radius);
+            }
+
+            let plane = PlanetSettings {
+                shape: BaseShape::Plane,
+                plane_size: 4.0,
+                radius,
+                ..default()
+            };
+            let (point, normal) = surface_point(Vec3::new(radius, 5.0, -radius), &plane);
+            assert_eq!((point, normal), (Vec3::new(radius, 0.0, -radius), Vec3::Y));
+            // Points past the edge stay on the plane.
+            let (edge, _) = surface_point(Vec3::new(10.0 * radius, 0.0, 0.0), &plane);
+            assert_eq!(edge, Vec3::new(2.0 * radius, 0.0, 0.0));
+            let mesh = surface_mesh(&plane, Vec3::Y).unwrap();
+            let reach = positions(&mesh)
+                .into_iter()
+                .map(|position| position[0].abs().max(position[2].abs()))
+                .fold(0.0, f32::max);
+            assert!((reach - 2.0 * radius).abs() < 1e-4 * radius);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use serde::{Deserialize, Serialize};
+
+use crate::noise::{fbm, seed_offset};
+use crate::persistence;
+
+/// The width of the blend between bare surface and ice, in degrees of latitude.
+const EDGE_BLEND_DEGREES: f32 = 1.5;
+
+/// The settings of the polar ice caps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IceCaps {
+    pub enabled: bool,
+    /// The latitude, in degrees, above which the surface is covered in ice.
+    pub latitude: f32,
+    /// How far, in degrees, the noisy cap edge strays from `latitude`.
+    pub boundary_noise: f32,
+    /// How far the ice sheet is raised above the surface, relative to the planet radius.
+    pub thickness: f32,
+    #[serde(with = "persistence::srgba")]
+    pub color: Color,
+}
+
+impl Default for IceCaps {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            latitude: 70.0,
+            boundary_noise: 6.0,
+            thickness: 0.01,
+            color: Color::srgb(0.95, 0.97, 1.0),
+        }
+    }
+}
+
+impl IceCaps {
+    /// Returns how much of the surface in the given direction is covered in ice, from 0 to 1,
+    /// with the cap edge wandering as the planet `seed` selects.
+    pub fn coverage(&self, direction: Vec3, seed: u32) -> f32 {
+        let direction = direction.normalize();
+        let latitude = direction.y.clamp(-1.0, 1.0).asin().to_degrees().abs();
+        let wander = fbm(direction * 4.0 + seed_offset(seed), 4) * 2.0 - 1.0;
+        let edge = self.latitude + wander * self.boundary_noise;
+        ((latitude - edge) / EDGE_BLEND_DEGREES + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+/// Colors the mesh's vertices by ice coverage and raises the ice sheet. The ice is blended over
+/// the vertex colors already on the mesh, or over `base_color` if it has none.
+///
+/// The normals are left as they were, so they must be recomputed if the sheet has a thickness.
+pub fn apply_ice_caps(mesh: &mut Mesh, ice: &IceCaps, seed: u32, base_color: Color) {
+    let base_colors = match mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR) {
+        Some(VertexAttributeValues::Float32x4(colors)) => Some(colors),
+        _ => None,
+    };
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let base = base_color.to_linear();
+    let ice_color = ice.color.to_linear();
+    let mut colors = Vec::with_capacity(positions.len());
+    for (index, position) in positions.iter_mut().enumerate() {
+        let point = Vec3::from(*position);
+        let coverage = ice.coverage(point, seed);
+        *position = (point * (1.0 + ice.thickness * coverage)).into();
+        let base = base_colors
+            .as_ref()
+            .and_then(|colors| colors.get(index))
+            .map_or(base, |&color| LinearRgba::from_f32_array(color));
+        colors.push(base.mix(&ice_color, coverage).to_f32_array());
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy_egui::EguiContexts;
+
+use crate::picking::PlanetPicker;
+
+/// The triangle of a planet face currently under the cursor.
+#[derive(Clone, Debug)]
+pub struct PickedTriangle {
+    pub entity: Entity,
+    pub triangle_index: usize,
+    pub indices: [u32; 3],
+    pub vertices: [Vec3; 3],
+    /// The world-space corners of every other triangle sharing a vertex with this one.
+    pub one_ring: Vec<[Vec3; 3]>,
+}
+
+impl PickedTriangle {
+    pub fn area(&self) -> f32 {
+        let [a, b, c] = self.vertices;
+        triangle_area(a, b, c)
+    }
+}
+
+/// Returns the area of the triangle with the given corners.
+pub fn triangle_area(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    (b - a).cross(c - a).length() * 0.5
+}
+
+/// A resource holding the triangle inspection tool state.
+#[derive(Resource, Default, Debug)]
+pub struct TriangleInspector {
+    pub enabled: bool,
+    pub show_one_ring: bool,
+    pub picked: Option<PickedTriangle>,
+    /// The triangles around each vertex of the last mesh a one-ring was found in.
+    adjacency: Option<(AssetId<Mesh>, Vec<Vec<usize>>)>,
+}
+
+pub struct InspectPlugin;
+
+impl Plugin for InspectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TriangleInspector>()
+            .add_systems(Update, (pick_triangle, draw_picked_triangle).chain());
+    }
+}
+
+/// Raycasts the cursor into the planet and records the exact triangle hit.
+fn pick_triangle(
+    mut contexts: EguiContexts,
+    mut inspector: ResMut<TriangleInspector>,
+    mut picker: PlanetPicker,
+    meshes: Res<Assets<Mesh>>,
+    q_meshes: Query<(&Mesh3d, &GlobalTransform)>,
+) {
+    if !inspector.enabled {
+        if inspector.picked.is_some() {
+            inspector.picked = None;
+        }
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_pointer_input()
+    {
+        return;
+    }
+
+    let inspector = inspector.as_mut();
+    inspector.picked = picker.cursor_hit().and_then(|(entity, hit)| {
+        let triangle_index = hit.triangle_index?;
+        let (mesh_3d, transform) = q_meshes.get(entity).ok()?;
+        let mesh = meshes.get(mesh_3d)?;
+        let indices = triangle_indices(mesh, triangle_index)?;
+        let one_ring = if inspector.show_one_ring {
+            if inspector
+                .adjacency
+                .as_ref()
+                .is_none_or(|(id, _)| *id != mesh_3d.id())
+            {
+                inspector.adjacency = Some((mesh_3d.id(), vertex_triangles(mesh)));
+            }
+            let (_, adjacency) = inspector.adjacency.as_ref()?;
+            one_ring(mesh, adjacency, triangle_index, indices, transform)
+        } else {
+            Vec::new()
+        };
+        Some(PickedTriangle {
+            entity,
+            triangle_index,
+            indices,
+            vertices: hit.triangle?,
+            one_ring,
+        })
+    });
+}
+
+/// Returns the three vertex indices of a triangle in the mesh's index buffer.
+fn triangle_indices(mesh: &Mesh, triangle_index: usize) -> Option<[u32; 3]> {
+    let start = triangle_index * 3;
+    Some(match mesh.indices()? {
+        Indices::U32(indices) => [indices[start], indices[start + 1], indices[start + 2]],
+        Indices::U16(indices) => {
+            [indices[start], indices[start + 1], indices[start + 2]].map(u32::from)
+        }
+    })
+}
+
+/// Lists, for every vertex, the triangles that use it.
+fn vertex_triangles(mesh: &Mesh) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); mesh.count_vertices()];
+    if let Some(indices) = mesh.indices() {
+        for (corner, vertex) in indices.iter().enumerate() {
+            adjacency[vertex].push(corner / 3);
+        }
+    }
+    adjacency
+}
+
+/// Collects the world-space corners of the triangles sharing a vertex with the given one.
+fn one_ring(
+    mesh: &Mesh,
+    adjacency: &[Vec<usize>],
+    triangle_index: usize,
+    indices: [u32; 3],
+    transform: &GlobalTransform,
+) -> Vec<[Vec3; 3]> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return Vec::new();
+    };
+    let mut ring: Vec<usize> = indices
+        .iter()
+        .flat_map(|&vertex| adjacency[vertex as usize].iter().copied())
+        .filter(|&triangle| triangle != triangle_index)
+        .collect();
+    ring.sort_unstable();
+    ring.dedup();
+    ring.into_iter()
+        .filter_map(|triangle| triangle_indices(mesh, triangle))
+        .map(|corners| {
+            corners.map(|vertex| transform.transform_point(Vec3::from(positions[vertex as usize])))
+        })
+        .collect()
+}
+
+/// Outlines the picked triangle's three edges, and its one-ring neighborhood when enabled.
+fn draw_picked_triangle(mut gizmos: Gizmos, inspector: Res<TriangleInspector>) {
+    if let Some(picked) = &inspector.picked {
+        for [a, b, c] in &picked.one_ring {
+            gizmos.linestrip([*a, *b, *c, *a], Color::srgb(0.2, 0.8, 1.0));
+        }
+        let [a, b, c] = picked.vertices;
+        gizmos.linestrip([a, b, c, a], Color::srgb(1.0, 0.2, 0.8));
+    }
+}
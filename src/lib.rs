@@ -0,0 +1,2557 @@
+use bevy::color::Srgba;
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::{
+    camera::ScalingMode,
+    mesh::PrimitiveTopology,
+    mesh::VertexAttributeValues,
+    render_asset::RenderAssetUsages,
+    render_resource::{Face, TextureUsages},
+};
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod axes;
+mod build_up;
+pub mod console;
+mod decals;
+mod diagnostics;
+mod elevation;
+mod erosion;
+mod export;
+mod flight_paths;
+pub mod generation;
+mod ice_caps;
+mod inspect;
+mod lighting;
+mod noise;
+mod ocean;
+mod persistence;
+mod picking;
+mod presets;
+mod preview;
+mod screenshot;
+mod sculpt;
+mod shortcuts;
+mod texture;
+mod toon;
+mod wireframe;
+
+use axes::{FaceAxes, FaceAxesPlugin};
+use build_up::{BuildUpPlugin, ResolutionBuildUp};
+use console::ConsolePlugin;
+use decals::{DecalPlugin, DecalTool, Decals};
+use diagnostics::{DiagnosticsPlugin, FrameStatsOverlay, SurfaceArea};
+use elevation::{ElevationColors, GradientStop};
+use erosion::Erosion;
+use export::{ExportPlugin, ExportRequest, ExportSettings};
+use flight_paths::{FlightPathPlugin, FlightPaths};
+use ice_caps::IceCaps;
+use inspect::{InspectPlugin, TriangleInspector};
+use lighting::LightingPlugin;
+use noise::{NoiseLayer, NoiseMode, random_seed};
+use ocean::OceanPlugin;
+use persistence::{AutoSave, PersistencePlugin, SETTINGS_PATH, SettingsFileRequest};
+use presets::Preset;
+use preview::{ResolutionPreview, ResolutionPreviewPlugin};
+use screenshot::{ScreenshotPlugin, ScreenshotRequest, ScreenshotSettings};
+use sculpt::{HeightBrush, HeightLayer, MIN_BRUSH_RADIUS, SculptPlugin};
+use shortcuts::{Shortcut, ShortcutsPlugin};
+use texture::{
+    BaseColorTexture, BaseColorTextureRequest, PlanetSurfaceMaterial, ProceduralTexturePlugin,
+};
+use toon::{Shading, ToonPlugin};
+use wireframe::{WireframeMode, WireframeOverlayPlugin};
+
+use generation::{
+    BaseShape, Culling, FACE_DIRECTIONS, MAX_ICOSPHERE_SUBDIVISIONS, MIN_RESOLUTION, MeshGenError,
+    MeshMemory, NormalShading, PlanetFace, PlanetSettings, UvProjection, face_visibility,
+    surface_mesh, surface_point, triangle_count, vertex_count, weld_seam_normals,
+};
+
+/// The longest frame delta seen by time-based systems, so hitches don't make animations jump.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(50);
+
+/// The gamepad button that resets the camera, like R on the keyboard.
+const GAMEPAD_RESET_BUTTON: GamepadButton = GamepadButton::North;
+
+/// How close, in radians or world units, the eased camera must get to its targets to snap
+/// onto them.
+const SMOOTHING_EPSILON: f32 = 1e-4;
+
+/// How much room is left around the planet when framing it, as a factor of its bounding radius.
+const FRAME_MARGIN: f32 = 1.05;
+
+/// The settings the face meshes are generated from; changes to anything else, such as the
+/// material or wireframe, are applied without regenerating.
+#[derive(Debug, Clone, PartialEq)]
+struct SurfaceGeometry {
+    resolution: u32,
+    shape: BaseShape,
+    spherify: bool,
+    flattening: f32,
+    radius: f32,
+    normal_shading: NormalShading,
+    seed: u32,
+    variation: f32,
+    noise_layers: Vec<NoiseLayer>,
+    erosion: Option<Erosion>,
+    lateral_strength: f32,
+    seam_weld_epsilon: f32,
+    uv_projection: UvProjection,
+    plane_size: f32,
+    /// The ice caps and the base color they are blended over, when enabled.
+    ice_caps: Option<(IceCaps, Color)>,
+    elevation_colors: Option<ElevationColors>,
+    sculpt: HeightLayer,
+}
+
+impl SurfaceGeometry {
+    fn new(settings: &PlanetSettings) -> Self {
+        Self {
+            resolution: settings.resolution,
+            shape: settings.shape,
+            spherify: settings.spherify,
+            flattening: settings.flattening,
+            radius: settings.radius,
+            normal_shading: settings.normal_shading,
+            seed: settings.seed,
+            variation: settings.variation,
+            noise_layers: settings.noise_layers.clone(),
+            erosion: settings.erosion.enabled.then(|| settings.erosion.clone()),
+            lateral_strength: settings.lateral_strength,
+            seam_weld_epsilon: settings.seam_weld_epsilon,
+            uv_projection: settings.uv_projection,
+            plane_size: settings.plane_size,
+            ice_caps: settings
+                .ice_caps
+                .enabled
+                .then(|| (settings.ice_caps.clone(), settings.color)),
+            elevation_colors: settings
+                .elevation_colors
+                .enabled
+                .then(|| settings.elevation_colors.clone()),
+            sculpt: settings.sculpt.clone(),
+        }
+    }
+}
+
+/// A resource holding the geometry settings the face meshes were last generated, or are being
+/// generated, from.
+#[derive(Resource, Default)]
+struct AppliedGeometry(Option<SurfaceGeometry>);
+
+/// A resource holding geometry rebuilds back until the settings have stopped changing for
+/// `delay`, so scrubbing a slider doesn't regenerate every frame.
+#[derive(Resource, Debug)]
+struct RegenerationDebounce {
+    /// How long, in seconds, the geometry must stay unchanged before it is rebuilt.
+    delay: f32,
+    timer: Timer,
+    /// The geometry waiting for the settings to settle.
+    waiting: Option<SurfaceGeometry>,
+}
+
+impl Default for RegenerationDebounce {
+    fn default() -> Self {
+        Self {
+            delay: 0.15,
+            timer: Timer::default(),
+            waiting: None,
+        }
+    }
+}
+
+/// Tracks which geometry the faces have, which is waiting to be built and which is being built.
+#[derive(SystemParam)]
+struct Regeneration<'w> {
+    time: Res<'w, Time>,
+    applied: ResMut<'w, AppliedGeometry>,
+    debounce: ResMut<'w, RegenerationDebounce>,
+    generation: ResMut<'w, FaceGeneration>,
+    build_up: Res<'w, ResolutionBuildUp>,
+}
+
+/// Face meshes being generated in the background.
+struct PendingFaces {
+    settings: Arc<PlanetSettings>,
+    usage: RenderAssetUsages,
+    tasks: Vec<(Entity, Task<Result<Mesh, MeshGenError>>)>,
+    finished: Vec<(Entity, Mesh)>,
+}
+
+/// A resource holding the generation in flight, if any.
+///
+/// Starting a new generation replaces the old one, and dropping its tasks cancels them.
+#[derive(Resource, Default)]
+struct FaceGeneration(Option<PendingFaces>);
+
+impl FaceGeneration {
+    /// Returns the number of finished faces and the total, while a generation is in flight.
+    fn progress(&self) -> Option<(usize, usize)> {
+        self.0.as_ref().map(|pending| {
+            let finished = pending.finished.len();
+            (finished, finished + pending.tasks.len())
+        })
+    }
+}
+
+/// Decides which asset usages the face meshes get from the features that read them.
+#[derive(SystemParam)]
+struct MeshUsage<'w, 's> {
+    inspector: Res<'w, TriangleInspector>,
+    decal_tool: Res<'w, DecalTool>,
+    brush: Res<'w, HeightBrush>,
+    flight_paths: Res<'w, FlightPaths>,
+    /// The usages the current face meshes were created with.
+    current: Local<'s, Option<RenderAssetUsages>>,
+}
+
+impl MeshUsage<'_, '_> {
+    fn for_settings(&self, settings: &PlanetSettings) -> RenderAssetUsages {
+        let cpu_access_needed = self.inspector.enabled
+            || self.decal_tool.placing
+            || self.brush.enabled
+            || self.flight_paths.placing
+            || (settings.wireframe && settings.wireframe_mode == WireframeMode::Barycentric);
+        settings.mesh_memory.usage(cpu_access_needed)
+    }
+}
+
+/// A resource holding the triangle count the generated surface should stay within.
+#[derive(Resource, Debug)]
+struct TriangleBudget(u64);
+
+impl Default for TriangleBudget {
+    fn default() -> Self {
+        Self(500_000)
+    }
+}
+
+/// A resource holding the size of the surface the visible faces were last generated with.
+#[derive(Resource, Debug, Default)]
+struct MeshStats {
+    vertices: u64,
+    triangles: u64,
+}
+
+impl MeshStats {
+    fn new(settings: &PlanetSettings) -> Self {
+        Self {
+            vertices: vertex_count(settings.shape, settings.resolution, settings.normal_shading),
+            triangles: triangle_count(settings.shape, settings.resolution),
+        }
+    }
+
+    /// Returns the size of the 32-bit index buffers, in megabytes.
+    fn index_megabytes(&self) -> f64 {
+        (self.triangles * 3 * size_of::<u32>() as u64) as f64 / 1e6
+    }
+}
+
+/// A resource holding the vertical field of view of the perspective camera, in radians.
+#[derive(Resource, Debug)]
+struct CameraFov(f32);
+
+impl Default for CameraFov {
+    fn default() -> Self {
+        Self(PerspectiveProjection::default().fov)
+    }
+}
+
+/// A resource to hold the request to fit the whole planet in view.
+#[derive(Resource, Default, Debug)]
+struct CameraFraming {
+    /// Whether the camera is framed again each time the faces are rebuilt.
+    auto: bool,
+    /// Whether the camera should be framed on the next update.
+    pending: bool,
+    /// The planet radius the faces were last rebuilt with; the camera is framed again when it
+    /// changes, so the planet stays in view.
+    radius: Option<f32>,
+}
+
+/// A component to identify the entity the planet faces and decals hang from, which carries the
+/// turntable spin.
+#[derive(Component)]
+struct PlanetRoot;
+
+/// A resource to hold the turntable spin of the planet about the Y axis.
+#[derive(Resource, Debug)]
+struct PlanetSpin {
+    enabled: bool,
+    /// How fast the planet turns, in radians per second.
+    speed: f32,
+    angle: f32,
+}
+
+impl Default for PlanetSpin {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed: 0.2,
+            angle: 0.0,
+        }
+    }
+}
+
+/// A named camera view that can be recalled later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CameraBookmark {
+    name: String,
+    center: Vec3,
+    radius: f32,
+    pitch: f32,
+    yaw: f32,
+    upside_down: bool,
+}
+
+impl CameraBookmark {
+    fn capture(name: String, state: &PanOrbitState) -> Self {
+        Self {
+            name,
+            center: state.center,
+            radius: state.radius,
+            pitch: state.pitch,
+            yaw: state.yaw,
+            upside_down: state.upside_down,
+        }
+    }
+
+    /// Returns the settled orbit state looking from the saved view.
+    fn state(&self) -> PanOrbitState {
+        PanOrbitState {
+            center: self.center,
+            radius: self.radius,
+            pitch: self.pitch,
+            yaw: self.yaw,
+            upside_down: self.upside_down,
+            locked_pan_axis: None,
+            target_center: self.center,
+            target_radius: self.radius,
+            target_pitch: self.pitch,
+            target_yaw: self.yaw,
+        }
+    }
+}
+
+/// A resource holding the saved camera views, in the order they were saved.
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+struct CameraBookmarks(Vec<CameraBookmark>);
+
+impl CameraBookmarks {
+    /// Returns the first "View n" name not already taken.
+    fn next_name(&self) -> String {
+        (1..)
+            .map(|n| format!("View {n}"))
+            .find(|name| self.0.iter().all(|bookmark| &bookmark.name != name))
+            .unwrap_or_default()
+    }
+}
+
+/// A resource to hold the handle to the planet's single material.
+#[derive(Resource)]
+struct PlanetMaterial(Handle<PlanetSurfaceMaterial>);
+
+/// The planet editor: the generated planet, its orbit camera and every tool and panel.
+///
+/// Expects `DefaultPlugins` and `EguiPlugin` to be added alongside it.
+pub struct PlanetEditorPlugin;
+
+impl Plugin for PlanetEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            WireframeOverlayPlugin,
+            DecalPlugin,
+            ResolutionPreviewPlugin,
+            PersistencePlugin,
+            DiagnosticsPlugin,
+            InspectPlugin,
+            ExportPlugin,
+            LightingPlugin,
+            ProceduralTexturePlugin,
+            FaceAxesPlugin,
+            ConsolePlugin,
+            (
+                SculptPlugin,
+                BuildUpPlugin,
+                FlightPathPlugin,
+                OceanPlugin,
+                ToonPlugin,
+                ShortcutsPlugin,
+                ScreenshotPlugin,
+            ),
+        ))
+        .insert_resource(Time::<Virtual>::from_max_delta(MAX_FRAME_DELTA))
+        .init_resource::<PlanetSettings>()
+        .init_resource::<TriangleBudget>()
+        .init_resource::<MeshStats>()
+        .init_resource::<CameraFov>()
+        .init_resource::<CameraFraming>()
+        .init_resource::<CameraBookmarks>()
+        .init_resource::<PlanetSpin>()
+        .init_resource::<AppliedGeometry>()
+        .init_resource::<FaceGeneration>()
+        .init_resource::<RegenerationDebounce>()
+        .add_systems(Startup, (setup_camera, setup_planet))
+        .add_systems(
+            Update,
+            (
+                pan_orbit_camera,
+                smooth_camera,
+                apply_camera_projection,
+                reset_camera,
+                toggle_spherify,
+                animate_camera_transition,
+                apply_planet_settings,
+                finish_face_generation,
+                frame_planet,
+                spin_planet,
+            )
+                .chain(),
+        )
+        .add_systems(EguiPrimaryContextPass, ui_editor);
+    }
+}
+
+/// Creates the initial 6 faces of the planet and the shared material.
+fn setup_planet(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<PlanetSurfaceMaterial>>,
+    mut stats: ResMut<MeshStats>,
+    settings: Res<PlanetSettings>,
+) {
+    *stats = MeshStats::new(&settings);
+    // Create the material and store its handle in a resource
+    let material_handle = materials.add(PlanetSurfaceMaterial {
+        base: StandardMaterial {
+            base_color: material_base_color(&settings, false),
+            metallic: settings.metallic,
+            perceptual_roughness: settings.roughness,
+            cull_mode: material_cull_mode(&settings),
+            ..default()
+        },
+        extension: settings
+            .texture
+            .extension(settings.radius)
+            .with_backface_highlight(settings.highlight_backfaces),
+    });
+    commands.insert_resource(PlanetMaterial(material_handle.clone()));
+
+    let root = commands
+        .spawn((PlanetRoot, Transform::default(), Visibility::default()))
+        .id();
+    for normal in FACE_DIRECTIONS {
+        // A face that fails to generate starts out empty and is filled in by a later rebuild.
+        let mesh = surface_mesh(&settings, normal).unwrap_or_else(|err| {
+            error!("Failed to generate the {normal} face: {err}");
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+        });
+
+        commands.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material_handle.clone()),
+            Transform::default(),
+            face_visibility(&settings, normal),
+            PlanetFace { normal },
+            ChildOf(root),
+        ));
+    }
+}
+
+/// Regenerates meshes and updates material color if settings have changed.
+fn apply_planet_settings(
+    settings: Res<PlanetSettings>,
+    planet_material: Res<PlanetMaterial>,
+    mut materials: ResMut<Assets<PlanetSurfaceMaterial>>,
+    query: Query<(Entity, &PlanetFace)>,
+    mut mesh_usage: MeshUsage,
+    mut regeneration: Regeneration,
+) {
+    let usage = mesh_usage.for_settings(&settings);
+    let usage_changed = *mesh_usage.current != Some(usage);
+    if settings.is_changed() {
+        // Update color and texture
+        if let Some(material) = materials.get_mut(&planet_material.0) {
+            let textured = material.base.base_color_texture.is_some();
+            material.base.base_color = material_base_color(&settings, textured);
+            material.base.metallic = settings.metallic;
+            material.base.perceptual_roughness = settings.roughness;
+            material.base.cull_mode = material_cull_mode(&settings);
+            material.extension = settings
+                .texture
+                .extension(settings.radius)
+                .with_backface_highlight(settings.highlight_backfaces);
+        }
+    }
+
+    let debounce = &mut regeneration.debounce;
+    if settings.is_changed() || usage_changed {
+        let geometry = SurfaceGeometry::new(&settings);
+        if !usage_changed && regeneration.applied.0.as_ref() == Some(&geometry) {
+            debounce.waiting = None;
+        } else if debounce.waiting.as_ref() != Some(&geometry) {
+            // Every further change restarts the wait.
+            debounce.timer = Timer::from_seconds(debounce.delay, TimerMode::Once);
+            debounce.waiting = Some(geometry);
+        }
+    }
+    if debounce.waiting.is_none() {
+        return;
+    }
+    // Usage changes come from toggling tools rather than dragging, and the build-up animation
+    // paces its own steps, so both apply right away.
+    let settled = debounce.timer.tick(regeneration.time.delta()).finished();
+    if !settled && !usage_changed && !regeneration.build_up.is_active() {
+        return;
+    }
+    let Some(geometry) = debounce.waiting.take() else {
+        return;
+    };
+    *mesh_usage.current = Some(usage);
+
+    // Regenerate meshes in the background, one task per face.
+    let shared = Arc::new(settings.clone());
+    let pool = AsyncComputeTaskPool::get();
+    let tasks = query
+        .iter()
+        .map(|(entity, face)| {
+            let settings = shared.clone();
+            let normal = face.normal;
+            let task = pool.spawn(async move { surface_mesh(&settings, normal) });
+            (entity, task)
+        })
+        .collect();
+    regeneration.generation.0 = Some(PendingFaces {
+        settings: shared,
+        usage,
+        tasks,
+        finished: Vec::new(),
+    });
+    regeneration.applied.0 = Some(geometry);
+}
+
+/// Collects the faces generated in the background and swaps them in together once all are done.
+fn finish_face_generation(
+    mut generation: ResMut<FaceGeneration>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut framing: ResMut<CameraFraming>,
+    mut stats: ResMut<MeshStats>,
+    mut area: ResMut<SurfaceArea>,
+    mut query: Query<(&mut Mesh3d, &mut Visibility, &PlanetFace)>,
+) {
+    let Some(pending) = &mut generation.0 else {
+        return;
+    };
+    pending.tasks.retain_mut(|(entity, task)| {
+        if !task.is_finished() {
+            return true;
+        }
+        // A face that fails keeps its previous mesh.
+        match block_on(task) {
+            Ok(mesh) => pending.finished.push((*entity, mesh)),
+            Err(err) => error!("Failed to generate a face: {err}"),
+        }
+        false
+    });
+    if !pending.tasks.is_empty() {
+        return;
+    }
+    let Some(pending) = generation.0.take() else {
+        return;
+    };
+
+    let settings = &pending.settings;
+    let (entities, mut new_meshes): (Vec<Entity>, Vec<Mesh>) = pending.finished.into_iter().unzip();
+    if settings.welds_seams() {
+        weld_seam_normals(&mut new_meshes, settings.seam_weld_tolerance());
+    }
+    // The area is measured here, as the meshes may keep no CPU copy once they are rendered.
+    let visible = entities.iter().zip(&new_meshes).filter(|(entity, _)| {
+        query
+            .get(**entity)
+            .is_ok_and(|(_, _, face)| face_visibility(settings, face.normal) != Visibility::Hidden)
+    });
+    area.measure(visible.map(|(_, mesh)| mesh), settings);
+    for (entity, mut new_mesh) in entities.into_iter().zip(new_meshes) {
+        let Ok((mut mesh_3d, mut visibility, face)) = query.get_mut(entity) else {
+            continue;
+        };
+        new_mesh.asset_usage = pending.usage;
+        *mesh_3d = Mesh3d(meshes.add(new_mesh));
+        visibility.set_if_neq(face_visibility(settings, face.normal));
+    }
+    *stats = MeshStats::new(settings);
+    let resized = framing
+        .radius
+        .is_some_and(|radius| radius != settings.radius);
+    if framing.auto || resized {
+        framing.pending = true;
+    }
+    framing.radius = Some(settings.radius);
+}
+
+/// Returns the material's base color; vertex colors carry the surface color when ice caps or
+/// elevation colors are generated, and a base color texture carries it when `textured`, so the
+/// material must not tint them.
+fn material_base_color(settings: &PlanetSettings, textured: bool) -> Color {
+    let vertex_colored = settings.ice_caps.enabled || settings.elevation_colors.enabled;
+    if textured || (settings.shape.is_planet() && vertex_colored) {
+        Color::WHITE
+    } else {
+        settings.color
+    }
+}
+
+/// Returns the material's cull mode; highlighting back faces needs them drawn.
+fn material_cull_mode(settings: &PlanetSettings) -> Option<Face> {
+    if settings.highlight_backfaces {
+        None
+    } else {
+        settings.culling.face()
+    }
+}
+
+/// Resources edited by the tool sections of the editor UI.
+#[derive(SystemParam)]
+struct EditorTools<'w> {
+    decal_tool: ResMut<'w, DecalTool>,
+    decals: ResMut<'w, Decals>,
+    preview: ResMut<'w, ResolutionPreview>,
+    auto_save: ResMut<'w, AutoSave>,
+    settings_requests: EventWriter<'w, SettingsFileRequest>,
+    budget: ResMut<'w, TriangleBudget>,
+    stats: Res<'w, MeshStats>,
+    frame_stats: ResMut<'w, FrameStatsOverlay>,
+    face_axes: ResMut<'w, FaceAxes>,
+    export: ResMut<'w, ExportSettings>,
+    export_requests: EventWriter<'w, ExportRequest>,
+    screenshot: ResMut<'w, ScreenshotSettings>,
+    screenshot_requests: EventWriter<'w, ScreenshotRequest>,
+    brush: ResMut<'w, HeightBrush>,
+    build_up: ResMut<'w, ResolutionBuildUp>,
+    generation: Res<'w, FaceGeneration>,
+    fov: ResMut<'w, CameraFov>,
+    framing: ResMut<'w, CameraFraming>,
+    bookmarks: ResMut<'w, CameraBookmarks>,
+    spin: ResMut<'w, PlanetSpin>,
+    base_texture: ResMut<'w, BaseColorTexture>,
+    texture_requests: EventWriter<'w, BaseColorTextureRequest>,
+    debounce: ResMut<'w, RegenerationDebounce>,
+}
+
+/// UI for controlling planet settings and camera reset.
+fn ui_editor(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<PlanetSettings>,
+    mut tools: EditorTools,
+    mut q_camera: Query<(
+        &mut PanOrbitState,
+        &mut CameraTransition,
+        &mut PanOrbitSettings,
+    )>,
+    mut color_space: Local<ColorSpace>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Controls").show(ctx, |ui| {
+        ui.weak("Press F1 for keyboard shortcuts.");
+        if let Some((finished, total)) = tools.generation.progress() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("Generating… {finished}/{total} faces"));
+            });
+        }
+        ui.label("Planet Settings");
+        ui.horizontal(|ui| {
+            ui.label("Preset:");
+            let current = Preset::of(&settings);
+            egui::ComboBox::from_id_salt("preset")
+                .selected_text(current.label())
+                .show_ui(ui, |ui| {
+                    for preset in Preset::ALL {
+                        if ui
+                            .selectable_label(preset == current, preset.label())
+                            .clicked()
+                        {
+                            preset.apply(&mut settings);
+                        }
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Replaces the shape and colors; edits afterwards make it Custom.");
+        // The icosphere's resolution is how often each of its edges is split.
+        let (resolution_label, max_resolution) = if settings.shape == BaseShape::Icosphere {
+            ("Subdivisions", MAX_ICOSPHERE_SUBDIVISIONS)
+        } else {
+            ("Resolution", 256)
+        };
+        if tools.preview.enabled {
+            ui.add(
+                egui::Slider::new(&mut tools.preview.target, MIN_RESOLUTION..=max_resolution)
+                    .text(resolution_label),
+            );
+            ui.horizontal(|ui| {
+                let pending = tools.preview.target != settings.resolution;
+                if ui
+                    .add_enabled(pending, egui::Button::new("Apply Resolution"))
+                    .clicked()
+                {
+                    settings.resolution = tools.preview.target;
+                }
+                if ui
+                    .add_enabled(pending, egui::Button::new("Discard"))
+                    .clicked()
+                {
+                    tools.preview.target = settings.resolution;
+                }
+            });
+        } else {
+            ui.add(
+                egui::Slider::new(&mut settings.resolution, MIN_RESOLUTION..=max_resolution)
+                    .text(resolution_label),
+            );
+        }
+        let resolution = if tools.preview.enabled {
+            tools.preview.target
+        } else {
+            settings.resolution
+        };
+        triangle_budget_bar(
+            ui,
+            triangle_count(settings.shape, resolution),
+            &mut tools.budget,
+        );
+        ui.label(format!(
+            "Current mesh: {} vertices, {} triangles, {:.2} MB of indices",
+            tools.stats.vertices,
+            tools.stats.triangles,
+            tools.stats.index_megabytes()
+        ));
+        ui.checkbox(&mut tools.frame_stats.visible, "Show FPS overlay");
+        if ui
+            .checkbox(
+                &mut tools.preview.enabled,
+                "Preview resolution before applying",
+            )
+            .changed()
+        {
+            tools.preview.target = settings.resolution;
+        }
+        ui.add(egui::Slider::new(&mut tools.debounce.delay, 0.0..=1.0).text("Rebuild Delay (s)"))
+            .on_hover_text(
+                "How long the geometry must stay unchanged before the faces are rebuilt.",
+            );
+        egui::CollapsingHeader::new("Build-up Animation").show(ui, |ui| {
+            let build_up = &mut tools.build_up;
+            ui.add(
+                egui::Slider::new(&mut build_up.start, MIN_RESOLUTION..=256)
+                    .text("From Resolution"),
+            );
+            ui.add(
+                egui::Slider::new(&mut build_up.end, MIN_RESOLUTION..=256).text("To Resolution"),
+            );
+            ui.add(egui::Slider::new(&mut build_up.duration, 0.5..=20.0).text("Duration (s)"));
+            ui.add(
+                egui::Slider::new(&mut build_up.step_interval, 0.0..=1.0).text("Step Interval (s)"),
+            )
+            .on_hover_text("The shortest time between regenerations while ramping.");
+            if build_up.is_active() {
+                if ui.button("Stop").clicked() {
+                    build_up.stop();
+                }
+            } else if ui.button("Play").clicked() {
+                build_up.play();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Base Shape:");
+            egui::ComboBox::from_id_salt("base_shape")
+                .selected_text(format!("{:?}", settings.shape))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.shape, BaseShape::Planet, "Planet");
+                    ui.selectable_value(&mut settings.shape, BaseShape::Icosphere, "Icosphere")
+                        .on_hover_text("A planet of evenly sized triangles, without cube corners.");
+                    ui.selectable_value(&mut settings.shape, BaseShape::Plane, "Plane");
+                    ui.selectable_value(&mut settings.shape, BaseShape::UvSphere, "UV Sphere")
+                        .on_hover_text("Bevy's built-in sphere, for comparing shading.");
+                });
+        });
+        match settings.shape {
+            BaseShape::Planet | BaseShape::Icosphere => {
+                if settings.shape == BaseShape::Planet {
+                    ui.checkbox(&mut settings.spherify, "Spherify")
+                        .on_hover_text(format!(
+                            "Toggle with {}",
+                            shortcuts::TOGGLE_SPHERIFY.label()
+                        ));
+                }
+                ui.add(
+                    egui::Slider::new(&mut settings.flattening, 0.0..=0.5).text("Polar Flattening"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut settings.radius, 0.1..=10_000.0)
+                        .logarithmic(true)
+                        .text("Radius"),
+                )
+                .on_hover_text("In world units; the terrain and the camera scale with it.");
+                ui.horizontal(|ui| {
+                    ui.label("Normals:");
+                    egui::ComboBox::from_id_salt("normal_shading")
+                        .selected_text(format!("{:?}", settings.normal_shading))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut settings.normal_shading,
+                                NormalShading::Smooth,
+                                "Smooth",
+                            );
+                            ui.selectable_value(
+                                &mut settings.normal_shading,
+                                NormalShading::Flat,
+                                "Flat",
+                            )
+                            .on_hover_text("Faceted, with three vertices per triangle.");
+                        });
+                });
+                egui::CollapsingHeader::new(format!(
+                    "Noise Layers ({})",
+                    settings.noise_layers.len()
+                ))
+                .id_salt("noise_layers")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Seed:");
+                        ui.add(egui::DragValue::new(&mut settings.seed));
+                        if ui.button("Randomize").clicked() {
+                            settings.seed = random_seed();
+                        }
+                    });
+                    ui.add(egui::Slider::new(&mut settings.variation, 0.0..=1.0).text("Variation"))
+                        .on_hover_text("Nudges the seed's terrain, for planets close to this one.");
+                    let mut removed = None;
+                    for (index, layer) in settings.noise_layers.iter_mut().enumerate() {
+                        ui.push_id(index, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut layer.enabled, format!("Layer {}", index + 1));
+                                ui.toggle_value(&mut layer.mute, "M")
+                                    .on_hover_text("Mute: leave this layer out for now.");
+                                ui.toggle_value(&mut layer.solo, "S")
+                                    .on_hover_text("Solo: leave out every layer not soloed.");
+                                if ui.small_button("Remove").clicked() {
+                                    removed = Some(index);
+                                }
+                            });
+                            ui.add_enabled_ui(layer.enabled, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("Mode:");
+                                    egui::ComboBox::from_id_salt("noise_mode")
+                                        .selected_text(format!("{:?}", layer.mode))
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(
+                                                &mut layer.mode,
+                                                NoiseMode::Simple,
+                                                "Simple",
+                                            );
+                                            ui.selectable_value(
+                                                &mut layer.mode,
+                                                NoiseMode::Ridged,
+                                                "Ridged",
+                                            )
+                                            .on_hover_text(
+                                                "Sharp ridges instead of rolling hills.",
+                                            );
+                                            ui.selectable_value(
+                                                &mut layer.mode,
+                                                NoiseMode::RidgedMultifractal,
+                                                "Ridged Multifractal",
+                                            )
+                                            .on_hover_text(
+                                                "Mountain ranges with detailed peaks and smooth valleys.",
+                                            );
+                                        });
+                                });
+                                ui.add(
+                                    egui::Slider::new(&mut layer.frequency, 0.5..=32.0)
+                                        .logarithmic(true)
+                                        .text("Frequency"),
+                                );
+                                ui.add(
+                                    egui::Slider::new(&mut layer.amplitude, 0.0..=0.2)
+                                        .text("Amplitude"),
+                                );
+                                ui.add(
+                                    egui::Slider::new(&mut layer.octaves, 1..=8).text("Octaves"),
+                                );
+                                ui.add(
+                                    egui::Slider::new(&mut layer.lacunarity, 1.0..=4.0)
+                                        .text("Lacunarity"),
+                                )
+                                .on_hover_text("The frequency multiplier between octaves.");
+                                ui.add(
+                                    egui::Slider::new(&mut layer.persistence, 0.0..=1.0)
+                                        .text("Persistence"),
+                                )
+                                .on_hover_text("The amplitude multiplier between octaves.");
+                                if layer.mode == NoiseMode::RidgedMultifractal {
+                                    ui.add(
+                                        egui::Slider::new(&mut layer.ridge_offset, 0.5..=1.5)
+                                            .text("Ridge Offset"),
+                                    )
+                                    .on_hover_text("Higher values flatten the valleys.");
+                                    ui.add(
+                                        egui::Slider::new(&mut layer.ridge_gain, 0.0..=4.0)
+                                            .text("Ridge Gain"),
+                                    )
+                                    .on_hover_text(
+                                        "How much each octave's detail follows the ridges below it.",
+                                    );
+                                }
+                            });
+                        });
+                        ui.separator();
+                    }
+                    if let Some(index) = removed {
+                        settings.noise_layers.remove(index);
+                    }
+                    if ui.button("Add Layer").clicked() {
+                        settings.noise_layers.push(NoiseLayer::default());
+                    }
+                });
+                egui::CollapsingHeader::new("Erosion").show(ui, |ui| {
+                    let erosion = &mut settings.erosion;
+                    ui.checkbox(&mut erosion.enabled, "Hydraulic erosion")
+                        .on_hover_text("Raindrops wear valleys into the noise layers.");
+                    ui.add_enabled_ui(erosion.enabled, |ui| {
+                        ui.add(
+                            egui::Slider::new(&mut erosion.droplets, 1_000..=200_000)
+                                .logarithmic(true)
+                                .text("Droplets"),
+                        )
+                        .on_hover_text("More droplets carve deeper channels, but take longer.");
+                        ui.add(egui::Slider::new(&mut erosion.strength, 0.0..=1.0).text("Strength"));
+                    });
+                });
+                ui.add(
+                    egui::Slider::new(&mut settings.lateral_strength, 0.0..=2.0)
+                        .text("Lateral Strength"),
+                )
+                .on_hover_text(
+                    "Shifts the terrain sideways as well as outwards, for cliffs and overhangs.",
+                );
+                ui.add_enabled(
+                    settings.welds_seams(),
+                    egui::Slider::new(&mut settings.seam_weld_epsilon, 0.0..=1e-2)
+                        .logarithmic(true)
+                        .text("Seam Weld Epsilon"),
+                )
+                .on_hover_text(
+                    "Face vertices closer than this, relative to the radius, share one smooth normal.",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("UV Projection:");
+                    egui::ComboBox::from_id_salt("uv_projection")
+                        .selected_text(format!("{:?}", settings.uv_projection))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut settings.uv_projection,
+                                UvProjection::FaceGrid,
+                                "Face Grid",
+                            );
+                            ui.selectable_value(
+                                &mut settings.uv_projection,
+                                UvProjection::Equirectangular,
+                                "Equirectangular",
+                            );
+                        });
+                });
+            }
+            BaseShape::Plane => {
+                ui.add(egui::Slider::new(&mut settings.plane_size, 0.5..=20.0).text("Plane Size"));
+            }
+            BaseShape::UvSphere => {
+                ui.label(format!(
+                    "{} stacks, {} sectors",
+                    settings.resolution.max(MIN_RESOLUTION),
+                    settings.resolution.max(MIN_RESOLUTION) * 2
+                ));
+            }
+        }
+        ui.checkbox(&mut settings.wireframe, "Wireframe");
+        ui.checkbox(&mut tools.face_axes.visible, "Show Face Axes");
+        ui.add_enabled(
+            settings.wireframe,
+            egui::Slider::new(&mut settings.wireframe_depth_bias, 0.0..=2000.0)
+                .text("Wire Depth Bias"),
+        );
+        ui.add_enabled_ui(settings.wireframe, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Wire Mode:");
+                egui::ComboBox::from_id_salt("wireframe_mode")
+                    .selected_text(format!("{:?}", settings.wireframe_mode))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut settings.wireframe_mode,
+                            WireframeMode::Lines,
+                            "Lines",
+                        );
+                        ui.selectable_value(
+                            &mut settings.wireframe_mode,
+                            WireframeMode::Barycentric,
+                            "Barycentric",
+                        )
+                        .on_hover_text("Constant-width antialiased edges at any resolution.");
+                    });
+            });
+            ui.add_enabled(
+                settings.wireframe_mode == WireframeMode::Barycentric,
+                egui::Slider::new(&mut settings.wireframe_width, 0.5..=5.0).text("Wire Width (px)"),
+            );
+            ui.label("Wire Color:");
+            color_picker_widget(ui, &mut settings.wireframe_color, *color_space);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Base Color:");
+            egui::ComboBox::from_id_salt("color_space")
+                .selected_text(format!("{:?}", *color_space))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut *color_space, ColorSpace::Srgb, "Srgb");
+                    ui.selectable_value(&mut *color_space, ColorSpace::Linear, "Linear");
+                });
+        });
+        color_picker_widget(ui, &mut settings.color, *color_space);
+        ui.horizontal(|ui| {
+            ui.label("Texture File:");
+            ui.text_edit_singleline(&mut tools.base_texture.path)
+                .on_hover_text("An image path, absolute or relative to the assets folder");
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Load Texture").clicked() {
+                tools.texture_requests.write(BaseColorTextureRequest::Load);
+            }
+            if ui
+                .add_enabled(
+                    tools.base_texture.applied().is_some(),
+                    egui::Button::new("Clear Texture"),
+                )
+                .clicked()
+            {
+                tools.texture_requests.write(BaseColorTextureRequest::Clear);
+            }
+            if tools.base_texture.is_loading() {
+                ui.spinner();
+            } else if let Some(path) = tools.base_texture.applied() {
+                ui.label(format!("Showing {path}"));
+            }
+        });
+        ui.add(egui::Slider::new(&mut settings.metallic, 0.0..=1.0).text("Metallic"));
+        ui.add(egui::Slider::new(&mut settings.roughness, 0.0..=1.0).text("Roughness"));
+        ui.horizontal(|ui| {
+            ui.label("Culling:");
+            egui::ComboBox::from_id_salt("culling")
+                .selected_text(format!("{:?}", settings.culling))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.culling, Culling::Back, "Back");
+                    ui.selectable_value(&mut settings.culling, Culling::Front, "Front");
+                    ui.selectable_value(&mut settings.culling, Culling::None, "None");
+                });
+        })
+        .response
+        .on_hover_text("Cull front faces to reveal triangles wound inside-out.");
+        ui.checkbox(&mut settings.highlight_backfaces, "Highlight back faces")
+            .on_hover_text("Draws every back face in flat magenta, ignoring the cull mode.");
+        ui.horizontal(|ui| {
+            ui.label("Mesh Memory:");
+            egui::ComboBox::from_id_salt("mesh_memory")
+                .selected_text(format!("{:?}", settings.mesh_memory))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.mesh_memory, MeshMemory::Auto, "Auto");
+                    ui.selectable_value(
+                        &mut settings.mesh_memory,
+                        MeshMemory::MainAndRender,
+                        "Main and render world",
+                    );
+                    ui.selectable_value(
+                        &mut settings.mesh_memory,
+                        MeshMemory::RenderOnly,
+                        "Render world only",
+                    );
+                });
+        })
+        .response
+        .on_hover_text(
+            "Render-only meshes free their CPU copy after upload, halving mesh memory, \
+             but picking, decal placement and barycentric wires need the copy.",
+        );
+
+        ui.separator();
+
+        ui.label("Ocean");
+        ui.add_enabled(
+            settings.shape.is_planet(),
+            egui::Checkbox::new(&mut settings.show_ocean, "Show ocean"),
+        );
+        ui.add_enabled(
+            settings.shape.is_planet() && settings.show_ocean,
+            egui::Slider::new(&mut settings.ocean_level, 0.9..=1.1).text("Sea Level"),
+        );
+
+        ui.separator();
+
+        ui.add_enabled(
+            settings.shape.is_planet(),
+            egui::Checkbox::new(&mut settings.elevation_colors.enabled, "Color by elevation"),
+        )
+        .on_hover_text("Color the terrain from deep water through lowlands to snowy peaks");
+        ui.add_enabled_ui(
+            settings.shape.is_planet() && settings.elevation_colors.enabled,
+            |ui| gradient_editor(ui, &mut settings.elevation_colors, *color_space),
+        );
+
+        ui.separator();
+
+        ui.label("Ice Caps");
+        ui.add_enabled(
+            settings.shape.is_planet(),
+            egui::Checkbox::new(&mut settings.ice_caps.enabled, "Polar ice caps"),
+        );
+        ui.add_enabled_ui(
+            settings.shape.is_planet() && settings.ice_caps.enabled,
+            |ui| {
+                let ice = &mut settings.ice_caps;
+                ui.add(egui::Slider::new(&mut ice.latitude, 30.0..=90.0).text("Cap Latitude (°)"));
+                ui.add(
+                    egui::Slider::new(&mut ice.boundary_noise, 0.0..=20.0)
+                        .text("Boundary Noise (°)"),
+                );
+                ui.add(egui::Slider::new(&mut ice.thickness, 0.0..=0.05).text("Thickness"));
+                ui.label("Ice Color:");
+                color_picker_widget(ui, &mut ice.color, *color_space);
+            },
+        );
+
+        ui.separator();
+
+        ui.label("Procedural Texture");
+        ui.checkbox(&mut settings.texture.enabled, "Noise texture");
+        ui.add_enabled_ui(settings.texture.enabled, |ui| {
+            ui.add(egui::Slider::new(&mut settings.texture.scale, 0.5..=32.0).text("Scale"));
+            ui.add(egui::Slider::new(&mut settings.texture.octaves, 1..=8).text("Octaves"));
+            ui.add(egui::Slider::new(&mut settings.texture.strength, 0.0..=1.0).text("Strength"));
+            ui.label("Noise Color:");
+            color_picker_widget(ui, &mut settings.texture.color, *color_space);
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Shading:");
+            egui::ComboBox::from_id_salt("shading")
+                .selected_text(format!("{:?}", settings.shading))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.shading, Shading::Pbr, "PBR");
+                    ui.selectable_value(&mut settings.shading, Shading::Toon, "Toon");
+                });
+        });
+        ui.add_enabled_ui(settings.shading == Shading::Toon, |ui| {
+            ui.add(egui::Slider::new(&mut settings.toon.bands, 1..=8).text("Bands"));
+            ui.add(
+                egui::Slider::new(&mut settings.toon.outline_width, 0.0..=0.6)
+                    .text("Outline Width"),
+            );
+            ui.label("Outline Color:");
+            color_picker_widget(ui, &mut settings.toon.outline_color, *color_space);
+        });
+
+        ui.separator();
+
+        ui.label("Settings File");
+        ui.horizontal(|ui| {
+            if ui
+                .button("Save Settings")
+                .on_hover_text(format!("Write the settings to {SETTINGS_PATH}"))
+                .clicked()
+            {
+                tools.settings_requests.write(SettingsFileRequest::Save);
+            }
+            if ui
+                .button("Load Settings")
+                .on_hover_text(format!("Replace the settings with {SETTINGS_PATH}"))
+                .clicked()
+            {
+                tools.settings_requests.write(SettingsFileRequest::Load);
+            }
+        });
+
+        ui.separator();
+
+        ui.label("Auto-save");
+        ui.checkbox(&mut tools.auto_save.enabled, "Auto-save to recovery file");
+        ui.add_enabled(
+            tools.auto_save.enabled,
+            egui::Slider::new(&mut tools.auto_save.interval_secs, 5.0..=300.0).text("Interval (s)"),
+        );
+
+        ui.separator();
+
+        ui.label("Export");
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("export_format")
+                .selected_text(tools.export.format.label())
+                .show_ui(ui, |ui| {
+                    for format in ExportRequest::FORMATS {
+                        let option =
+                            ui.selectable_value(&mut tools.export.format, format, format.label());
+                        if matches!(format, ExportRequest::Obj | ExportRequest::Ply) {
+                            option.on_hover_text("Includes the ice cap and elevation colors.");
+                        }
+                    }
+                });
+            if ui.button("Export").clicked() {
+                tools.export_requests.write(tools.export.format);
+            }
+            if tools.export.format == ExportRequest::Svg {
+                ui.checkbox(
+                    &mut tools.export.svg_hidden_line_removal,
+                    "Hidden line removal",
+                );
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Export LOD Chain").clicked() {
+                tools.export_requests.write(ExportRequest::LodChain);
+            }
+            let mut levels = tools.export.lod_resolutions.len();
+            if ui
+                .add(egui::Slider::new(&mut levels, 1..=5).text("Levels"))
+                .changed()
+            {
+                let lods = &mut tools.export.lod_resolutions;
+                while lods.len() < levels {
+                    let last = lods.last().copied().unwrap_or(64);
+                    lods.push((last / 2).max(MIN_RESOLUTION));
+                }
+                lods.truncate(levels);
+            }
+        });
+        for (level, resolution) in tools.export.lod_resolutions.iter_mut().enumerate() {
+            ui.add(egui::Slider::new(resolution, MIN_RESOLUTION..=256).text(format!("LOD{level}")));
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button("Screenshot")
+                .on_hover_text("Save the view without the UI as a PNG")
+                .clicked()
+            {
+                tools.screenshot_requests.write(ScreenshotRequest);
+            }
+            ui.checkbox(&mut tools.screenshot.depth, "Depth").on_hover_text(
+                "Also save the depth buffer as a grayscale PNG, nearer brighter; \
+                 multisampling is off while this is on",
+            );
+            ui.label("Directory");
+            ui.text_edit_singleline(&mut tools.screenshot.directory);
+        });
+
+        ui.separator();
+
+        ui.label("Decals");
+        ui.checkbox(&mut tools.decal_tool.placing, "Place with left click");
+        ui.add(egui::Slider::new(&mut tools.decal_tool.size, 0.02..=0.5).text("Decal Size"));
+        ui.horizontal(|ui| {
+            ui.label(format!("{} placed", tools.decals.entries.len()));
+            if ui.button("Clear Decals").clicked() {
+                tools.decals.clear();
+            }
+        });
+
+        ui.separator();
+
+        ui.label("Sculpt");
+        ui.add_enabled(
+            settings.shape.is_planet(),
+            egui::Checkbox::new(&mut tools.brush.enabled, "Paint elevation"),
+        )
+        .on_hover_text(format!(
+            "Hold {} and drag with the left button to raise; add {} to lower.",
+            shortcuts::SCULPT.label(),
+            shortcuts::SCULPT_LOWER.label()
+        ));
+        ui.add(egui::Slider::new(&mut tools.brush.radius, MIN_BRUSH_RADIUS..=1.0).text("Brush Size"));
+        ui.add(
+            egui::Slider::new(&mut tools.brush.strength, 0.0005..=0.05)
+                .logarithmic(true)
+                .text("Brush Strength"),
+        );
+        ui.horizontal(|ui| {
+            ui.label(format!("{} stamps", settings.sculpt.stamps.len()));
+            if ui.button("Clear Elevation").clicked() {
+                settings.sculpt.stamps.clear();
+            }
+        });
+
+        ui.separator();
+
+        ui.label("Camera");
+        for (_, _, mut orbit_settings) in &mut q_camera {
+            ui.checkbox(&mut orbit_settings.orthographic, "Orthographic projection")
+                .on_hover_text(
+                    "Show the planet without perspective, e.g. to inspect its silhouette.",
+                );
+            let mut degrees = tools.fov.0.to_degrees();
+            if ui
+                .add_enabled(
+                    !orbit_settings.orthographic,
+                    egui::Slider::new(&mut degrees, 10.0..=120.0).text("Field of View (°)"),
+                )
+                .changed()
+            {
+                tools.fov.0 = degrees.to_radians();
+            }
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.orbit_ramp, 0.0..=1.5)
+                    .text("Orbit Speed Ramp"),
+            )
+            .on_hover_text("Slows orbiting near the surface and speeds it up far away.");
+            ui.checkbox(
+                &mut orbit_settings.center_on_surface,
+                "Keep orbit center on surface",
+            );
+            ui.checkbox(
+                &mut orbit_settings.pan_axis_lock,
+                "Hold Shift to pan along one axis",
+            );
+            ui.horizontal(|ui| {
+                ui.label("Zoom Curve:");
+                ui.selectable_value(
+                    &mut orbit_settings.zoom_curve,
+                    ZoomCurve::Exponential,
+                    "Exponential",
+                );
+                ui.selectable_value(&mut orbit_settings.zoom_curve, ZoomCurve::Linear, "Linear")
+                    .on_hover_text("Zoom in steps proportional to the height above the surface.");
+            });
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.min_radius, 1.0..=10.0)
+                    .text("Min Zoom Radius"),
+            )
+            .on_hover_text("In planet radii.");
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.max_radius, 2.0..=500.0)
+                    .logarithmic(true)
+                    .text("Max Zoom Radius"),
+            )
+            .on_hover_text("In planet radii.");
+            ui.checkbox(&mut orbit_settings.clamp_pitch, "Limit pitch")
+                .on_hover_text("Stop orbiting at the poles instead of going over them.");
+            ui.add_enabled_ui(orbit_settings.clamp_pitch, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut orbit_settings.min_pitch, -FRAC_PI_2..=0.0)
+                        .text("Min Pitch (rad)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut orbit_settings.max_pitch, 0.0..=FRAC_PI_2)
+                        .text("Max Pitch (rad)"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Reset Easing:");
+                egui::ComboBox::from_id_salt("reset_easing")
+                    .selected_text(orbit_settings.reset_easing.label())
+                    .show_ui(ui, |ui| {
+                        for easing in [Easing::Linear, Easing::EaseInOut, Easing::EaseOut] {
+                            ui.selectable_value(
+                                &mut orbit_settings.reset_easing,
+                                easing,
+                                easing.label(),
+                            );
+                        }
+                    });
+            });
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.smoothing, 0.0..=0.5).text("Smoothing (s)"),
+            )
+            .on_hover_text(
+                "How long the camera takes to catch up with input; 0 follows it instantly.",
+            );
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.key_orbit_speed, 0.1..=5.0)
+                    .text("Key Orbit Speed (rad/s)"),
+            );
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.key_pan_speed, 0.05..=2.0)
+                    .text("Key Pan Speed"),
+            );
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.key_zoom_speed, 0.1..=5.0)
+                    .text("Key Zoom Speed"),
+            );
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.gamepad_dead_zone, 0.0..=0.5)
+                    .text("Gamepad Dead Zone"),
+            );
+            ui.add(
+                egui::Slider::new(&mut orbit_settings.reset_duration, 0.0..=2.0)
+                    .text("Reset Duration (s)"),
+            );
+        }
+
+        ui.label(format!(
+            "Press '{}' to reset camera.",
+            shortcuts::RESET_CAMERA.label()
+        ));
+        if ui.button("Reset Camera Now").clicked() {
+            for (state, mut transition, _) in &mut q_camera {
+                transition.start(*state, PanOrbitState::default_position(settings.radius));
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button("Frame Planet")
+                .on_hover_text("Zoom so the whole planet fits in view, centered on it")
+                .clicked()
+            {
+                tools.framing.pending = true;
+            }
+            ui.checkbox(&mut tools.framing.auto, "Frame after rebuilds");
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut tools.spin.enabled, "Spin")
+                .on_hover_text(
+                    "Turn the planet like a turntable; holding the orbit button pauses it",
+                )
+                .changed()
+                && !tools.spin.enabled
+            {
+                tools.spin.angle = 0.0;
+            }
+            ui.add_enabled(
+                tools.spin.enabled,
+                egui::Slider::new(&mut tools.spin.speed, -2.0..=2.0).text("Speed (rad/s)"),
+            );
+        });
+
+        ui.separator();
+        ui.label("Saved Views");
+        if ui.button("Save Current View").clicked()
+            && let Some((state, _, _)) = q_camera.iter().next()
+        {
+            let name = tools.bookmarks.next_name();
+            tools.bookmarks.0.push(CameraBookmark::capture(name, state));
+        }
+        let mut removed = None;
+        for (index, bookmark) in tools.bookmarks.0.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.button(&bookmark.name).clicked() {
+                    for (state, mut transition, _) in &mut q_camera {
+                        transition.start(*state, bookmark.state());
+                    }
+                }
+                if ui.small_button("Remove").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            tools.bookmarks.0.remove(index);
+        }
+    });
+}
+
+/// The number of flat slices the gradient preview bar is drawn with.
+const GRADIENT_PREVIEW_SLICES: usize = 64;
+
+/// Edits the elevation gradient's stops under a bar previewing the sampled ramp.
+///
+/// Stops are re-sorted by height once a height edit ends rather than while dragging, so a row
+/// doesn't jump away from the cursor; moving a stop up or down swaps its color with its
+/// neighbor's.
+fn gradient_editor(ui: &mut egui::Ui, colors: &mut ElevationColors, space: ColorSpace) {
+    if let Some((low, high)) = colors.range() {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 16.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let width = rect.width() / GRADIENT_PREVIEW_SLICES as f32;
+        for slice in 0..GRADIENT_PREVIEW_SLICES {
+            let t = (slice as f32 + 0.5) / GRADIENT_PREVIEW_SLICES as f32;
+            let [r, g, b, _] = Srgba::from(colors.color_at(low + (high - low) * t)).to_u8_array();
+            let left = rect.left() + width * slice as f32;
+            painter.rect_filled(
+                egui::Rect::from_x_y_ranges(left..=left + width + 0.5, rect.y_range()),
+                0.0,
+                egui::Color32::from_rgb(r, g, b),
+            );
+        }
+    }
+
+    let count = colors.stops.len();
+    let mut resort = false;
+    let mut removed = None;
+    let mut swapped = None;
+    for (index, stop) in colors.stops.iter_mut().enumerate() {
+        ui.push_id(index, |ui| {
+            ui.horizontal(|ui| {
+                let height = ui.add(
+                    egui::DragValue::new(&mut stop.elevation)
+                        .range(-0.5..=0.5)
+                        .speed(0.001)
+                        .fixed_decimals(3)
+                        .prefix("Height "),
+                );
+                resort |= height.drag_stopped() || height.lost_focus();
+                if ui
+                    .add_enabled(index > 0, egui::Button::new("Up").small())
+                    .clicked()
+                {
+                    swapped = Some(index - 1);
+                }
+                if ui
+                    .add_enabled(index + 1 < count, egui::Button::new("Down").small())
+                    .clicked()
+                {
+                    swapped = Some(index);
+                }
+                if ui
+                    .add_enabled(count > 1, egui::Button::new("Remove").small())
+                    .clicked()
+                {
+                    removed = Some(index);
+                }
+            });
+            color_picker_widget(ui, &mut stop.color, space);
+        });
+    }
+    if let Some(index) = swapped {
+        let color = colors.stops[index].color;
+        colors.stops[index].color = colors.stops[index + 1].color;
+        colors.stops[index + 1].color = color;
+    }
+    if let Some(index) = removed {
+        colors.stops.remove(index);
+    }
+    if ui.button("Add Stop").clicked() {
+        let stop = colors.stops.last().map_or_else(
+            || GradientStop::new(0.0, Color::WHITE),
+            |last| GradientStop::new(last.elevation + 0.01, last.color),
+        );
+        colors.stops.push(stop);
+        resort = true;
+    }
+    if resort {
+        colors.sort_stops();
+    }
+}
+
+/// Shows the triangle count against the budget as a bar that turns yellow, then red, as it fills.
+fn triangle_budget_bar(ui: &mut egui::Ui, triangles: u64, budget: &mut TriangleBudget) {
+    let fraction = triangles as f32 / budget.0.max(1) as f32;
+    let fill = if fraction > 1.0 {
+        egui::Color32::from_rgb(200, 60, 50)
+    } else if fraction > 0.75 {
+        egui::Color32::from_rgb(210, 170, 40)
+    } else {
+        egui::Color32::from_rgb(60, 160, 70)
+    };
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::ProgressBar::new(fraction.min(1.0))
+                .fill(fill)
+                .desired_width(180.0)
+                .text(format!(
+                    "{triangles} / {} triangles ({:.0}%)",
+                    budget.0,
+                    fraction * 100.0
+                )),
+        );
+        ui.add(
+            egui::DragValue::new(&mut budget.0)
+                .range(1_000..=50_000_000)
+                .speed(1_000.0)
+                .prefix("Budget "),
+        );
+    });
+}
+
+/// The color space in which the picker's numeric components are edited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+impl ColorSpace {
+    /// Returns the color's components in this space.
+    fn components(self, color: Color) -> [f32; 4] {
+        match self {
+            ColorSpace::Srgb => Srgba::from(color).to_f32_array(),
+            ColorSpace::Linear => color.to_linear().to_f32_array(),
+        }
+    }
+
+    /// Returns the color with the given components in this space.
+    fn color(self, components: [f32; 4]) -> Color {
+        match self {
+            ColorSpace::Srgb => Srgba::from_f32_array(components).into(),
+            ColorSpace::Linear => LinearRgba::from_f32_array(components).into(),
+        }
+    }
+}
+
+/// Converts a color to egui's linear color, which its color picker edits.
+fn to_egui_rgba(color: Color) -> egui::Rgba {
+    let linear = color.to_linear();
+    egui::Rgba::from_rgba_unmultiplied(linear.red, linear.green, linear.blue, linear.alpha)
+}
+
+fn from_egui_rgba(rgba: egui::Rgba) -> Color {
+    let [r, g, b, a] = rgba.to_rgba_unmultiplied();
+    Color::linear_rgba(r, g, b, a)
+}
+
+/// A helper function to create a color picker widget.
+///
+/// The color is only written back when the user edits it, and always through `f32`
+/// components, so an untouched color never drifts from repeated conversion.
+fn color_picker_widget(ui: &mut egui::Ui, color: &mut Color, space: ColorSpace) -> egui::Response {
+    ui.horizontal(|ui| {
+        let mut egui_color = to_egui_rgba(*color);
+        let mut res = egui::widgets::color_picker::color_edit_button_rgba(
+            ui,
+            &mut egui_color,
+            egui::color_picker::Alpha::Opaque,
+        );
+        if res.changed() {
+            *color = from_egui_rgba(egui_color);
+        }
+
+        let mut components = space.components(*color);
+        let mut changed = false;
+        for (component, prefix) in components.iter_mut().zip(["R ", "G ", "B "]) {
+            let drag = ui.add(
+                egui::DragValue::new(component)
+                    .range(0.0..=1.0)
+                    .speed(0.002)
+                    .fixed_decimals(3)
+                    .prefix(prefix),
+            );
+            changed |= drag.changed();
+            res |= drag;
+        }
+        if changed {
+            *color = space.color(components);
+        }
+        res
+    })
+    .inner
+}
+
+#[derive(Component, Clone, Copy)]
+struct PanOrbitState {
+    center: Vec3,
+    radius: f32,
+    upside_down: bool,
+    pitch: f32,
+    yaw: f32,
+    locked_pan_axis: Option<PanAxis>,
+    /// The values input moves the camera to; the ones above ease toward them.
+    target_center: Vec3,
+    target_radius: f32,
+    target_pitch: f32,
+    target_yaw: f32,
+}
+
+impl Default for PanOrbitState {
+    fn default() -> Self {
+        PanOrbitState {
+            center: Vec3::ZERO,
+            radius: 1.0,
+            upside_down: false,
+            pitch: 0.0,
+            yaw: 0.0,
+            locked_pan_axis: None,
+            target_center: Vec3::ZERO,
+            target_radius: 1.0,
+            target_pitch: 0.0,
+            target_yaw: 0.0,
+        }
+    }
+}
+
+impl PanOrbitState {
+    /// Returns the view the camera resets to, at a distance proportional to the planet radius.
+    fn default_position(planet_radius: f32) -> Self {
+        Self {
+            center: Vec3::ZERO,
+            radius: 6.0 * planet_radius,
+            pitch: 0.0,
+            yaw: 0.0,
+            upside_down: false,
+            locked_pan_axis: None,
+            target_center: Vec3::ZERO,
+            target_radius: 6.0 * planet_radius,
+            target_pitch: 0.0,
+            target_yaw: 0.0,
+        }
+    }
+
+    /// Moves the targets to the current values, dropping any easing still to come.
+    fn settle(&mut self) {
+        self.target_center = self.center;
+        self.target_radius = self.radius;
+        self.target_pitch = self.pitch;
+        self.target_yaw = self.yaw;
+    }
+
+    /// Keeps the target distance within `min_radius..=max_radius` and, when the settings clamp
+    /// the pitch, the target pitch within their limits.
+    fn clamp_targets(&mut self, settings: &PanOrbitSettings, min_radius: f32, max_radius: f32) {
+        self.target_radius = self.target_radius.clamp(min_radius, max_radius);
+        if settings.clamp_pitch {
+            self.target_pitch = self.target_pitch.clamp(
+                settings.min_pitch,
+                settings.max_pitch.max(settings.min_pitch),
+            );
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.center == self.target_center
+            && self.radius == self.target_radius
+            && self.pitch == self.target_pitch
+            && self.yaw == self.target_yaw
+    }
+}
+
+#[derive(Component)]
+struct PanOrbitSettings {
+    pan_sensitivity: f32,
+    orbit_sensitivity: f32,
+    orbit_ramp: f32,
+    center_on_surface: bool,
+    pan_axis_lock: bool,
+    reset_easing: Easing,
+    reset_duration: f32,
+    /// The time constant, in seconds, of the camera easing toward its targets; 0 follows input
+    /// instantly.
+    smoothing: f32,
+    zoom_sensitivity: f32,
+    zoom_curve: ZoomCurve,
+    /// Whether the camera uses an orthographic projection, sized so the orbit center plane
+    /// shows what the perspective view would.
+    orthographic: bool,
+    /// The closest the camera may zoom to the planet center, in planet radii; the near plane
+    /// also keeps it out of the surface.
+    min_radius: f32,
+    /// The farthest the camera may zoom out, in planet radii.
+    max_radius: f32,
+    /// Whether the pitch is kept within `min_pitch..=max_pitch`, for a turntable view that never
+    /// goes over the poles.
+    clamp_pitch: bool,
+    min_pitch: f32,
+    max_pitch: f32,
+    pan_button: Option<MouseButton>,
+    orbit_button: Option<MouseButton>,
+    zoom_button: Option<MouseButton>,
+    scroll_action: Option<PanOrbitAction>,
+    scroll_line_sensitivity: f32,
+    scroll_pixel_sensitivity: f32,
+    /// How fast the orbit keys and the gamepad's left stick turn the camera, in radians per second.
+    key_orbit_speed: f32,
+    /// How fast the pan keys move the center, in orbit radii per second.
+    key_pan_speed: f32,
+    /// How fast the dolly keys and the gamepad's zoom inputs zoom, in the zoom curve's units per
+    /// second.
+    key_zoom_speed: f32,
+    /// How far a gamepad stick must be pushed, from 0 to 1, before it moves the camera, so stick
+    /// drift doesn't slowly spin the view.
+    gamepad_dead_zone: f32,
+}
+
+/// How a zoom input changes the orbit radius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZoomCurve {
+    /// Scales the distance to the planet center, zooming faster far away.
+    Exponential,
+    /// Steps in proportion to the height above the surface, for fine control close to it.
+    Linear,
+}
+
+/// The screen axis panning is constrained to while axis lock is held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PanAxis {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PanOrbitAction {
+    Pan,
+    Orbit,
+    Zoom,
+}
+
+impl Default for PanOrbitSettings {
+    fn default() -> Self {
+        PanOrbitSettings {
+            pan_sensitivity: 0.001,
+            orbit_sensitivity: 0.1f32.to_radians(),
+            orbit_ramp: 0.0,
+            center_on_surface: false,
+            pan_axis_lock: true,
+            reset_easing: Easing::EaseInOut,
+            reset_duration: 0.6,
+            smoothing: 0.08,
+            zoom_sensitivity: 0.01,
+            zoom_curve: ZoomCurve::Exponential,
+            orthographic: false,
+            min_radius: 1.2,
+            max_radius: 100.0,
+            clamp_pitch: true,
+            // Just short of straight down and up, where yaw becomes ambiguous.
+            min_pitch: -FRAC_PI_2 + 0.01,
+            max_pitch: FRAC_PI_2 - 0.01,
+            pan_button: Some(MouseButton::Middle),
+            orbit_button: Some(MouseButton::Right),
+            zoom_button: None,
+            scroll_action: Some(PanOrbitAction::Zoom),
+            scroll_line_sensitivity: 16.0,
+            scroll_pixel_sensitivity: 1.0,
+            key_orbit_speed: 1.0,
+            key_pan_speed: 0.5,
+            key_zoom_speed: 1.0,
+            gamepad_dead_zone: 0.15,
+        }
+    }
+}
+
+impl PanOrbitSettings {
+    /// Scales the orbit sensitivity by the camera's height above the surface,
+    /// relative to the default view, raised to the power of `orbit_ramp`.
+    fn orbit_sensitivity_at(&self, radius: f32, planet_radius: f32) -> f32 {
+        let reference_altitude = PanOrbitState::default_position(1.0).radius - 1.0;
+        let altitude = ((radius / planet_radius - 1.0) / reference_altitude).max(0.05);
+        self.orbit_sensitivity * altitude.powf(self.orbit_ramp)
+    }
+}
+
+/// The curve used to interpolate animated camera moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Easing {
+    Linear,
+    EaseInOut,
+    EaseOut,
+}
+
+impl Easing {
+    /// Maps linear progress in `[0, 1]` to eased progress.
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseInOut => "Ease In-Out",
+            Easing::EaseOut => "Ease Out",
+        }
+    }
+}
+
+/// An animated move of the orbit camera from one state to another.
+#[derive(Component, Default)]
+struct CameraTransition {
+    from: PanOrbitState,
+    to: PanOrbitState,
+    elapsed: f32,
+    active: bool,
+}
+
+impl CameraTransition {
+    fn start(&mut self, from: PanOrbitState, to: PanOrbitState) {
+        *self = Self {
+            from,
+            to,
+            elapsed: 0.0,
+            active: true,
+        };
+    }
+}
+
+fn setup_camera(mut commands: Commands, settings: Res<PlanetSettings>) {
+    let transform = Transform::from_translation(Vec3::new(0.0, 2.0, 6.0) * settings.radius)
+        .looking_at(Vec3::ZERO, Vec3::Y);
+    let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+    let radius = transform.translation.length();
+    commands.spawn((
+        Camera3d {
+            // Lets screenshots copy the depth buffer out.
+            depth_texture_usages: (TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC)
+                .into(),
+            ..default()
+        },
+        transform,
+        PanOrbitState {
+            center: Vec3::ZERO,
+            radius,
+            upside_down: false,
+            pitch,
+            yaw,
+            locked_pan_axis: None,
+            target_center: Vec3::ZERO,
+            target_radius: radius,
+            target_pitch: pitch,
+            target_yaw: yaw,
+        },
+        PanOrbitSettings::default(),
+        CameraTransition::default(),
+    ));
+}
+
+/// The mouse input that drives the orbit camera.
+#[derive(SystemParam)]
+struct CameraMouseInput<'w, 's> {
+    buttons: Res<'w, ButtonInput<MouseButton>>,
+    motion: EventReader<'w, 's, MouseMotion>,
+    scroll: EventReader<'w, 's, MouseWheel>,
+}
+
+fn pan_orbit_camera(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    mut mouse: CameraMouseInput,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    planet: Res<PlanetSettings>,
+    mut q_camera: Query<(
+        &PanOrbitSettings,
+        &mut PanOrbitState,
+        &mut CameraTransition,
+        &Transform,
+        &Projection,
+    )>,
+) {
+    let (pointer_free, keyboard_free) = match contexts.ctx_mut() {
+        Ok(ctx) => (!ctx.wants_pointer_input(), !ctx.wants_keyboard_input()),
+        Err(_) => (true, true),
+    };
+    let mut total_motion: Vec2 = mouse.motion.read().map(|ev| ev.delta).sum();
+    total_motion.y = -total_motion.y;
+    let mut total_scroll_lines = Vec2::ZERO;
+    let mut total_scroll_pixels = Vec2::ZERO;
+    for ev in mouse.scroll.read() {
+        match ev.unit {
+            MouseScrollUnit::Line => {
+                total_scroll_lines.x += ev.x;
+                total_scroll_lines.y -= ev.y;
+            }
+            MouseScrollUnit::Pixel => {
+                total_scroll_pixels.x += ev.x;
+                total_scroll_pixels.y -= ev.y;
+            }
+        }
+    }
+    if !pointer_free {
+        total_motion = Vec2::ZERO;
+        total_scroll_lines = Vec2::ZERO;
+        total_scroll_pixels = Vec2::ZERO;
+    }
+    // Each held key pair gives -1, 0 or 1 along its axis.
+    let key_axis = |negative: &Shortcut, positive: &Shortcut| {
+        if !keyboard_free {
+            return 0.0;
+        }
+        let held = |shortcut: &Shortcut| if shortcut.pressed(&keys) { 1.0 } else { 0.0 };
+        held(positive) - held(negative)
+    };
+    let key_orbit = Vec2::new(
+        key_axis(&shortcuts::ORBIT_LEFT, &shortcuts::ORBIT_RIGHT),
+        key_axis(&shortcuts::ORBIT_UP, &shortcuts::ORBIT_DOWN),
+    );
+    let key_pan = Vec2::new(
+        key_axis(&shortcuts::PAN_LEFT, &shortcuts::PAN_RIGHT),
+        key_axis(&shortcuts::PAN_DOWN, &shortcuts::PAN_UP),
+    );
+    let key_zoom = key_axis(&shortcuts::DOLLY_OUT, &shortcuts::DOLLY_IN);
+    let gamepad = gamepads.iter().next();
+    let delta = time.delta_secs();
+    for (settings, mut state, mut transition, transform, projection) in &mut q_camera {
+        let planet_radius = planet.radius;
+        let min_radius =
+            (settings.min_radius * planet_radius).max(planet_radius + near_plane(projection));
+        let max_radius = (settings.max_radius * planet_radius).max(min_radius);
+        // The left stick orbits; the right stick and the triggers zoom.
+        let (pad_orbit, pad_zoom) = gamepad.map_or((Vec2::ZERO, 0.0), |gamepad| {
+            let dead_zone = settings.gamepad_dead_zone;
+            let left = apply_dead_zone(gamepad.left_stick(), dead_zone);
+            let right = apply_dead_zone(gamepad.right_stick(), dead_zone);
+            let trigger = |button| gamepad.get(button).unwrap_or(0.0);
+            let triggers =
+                trigger(GamepadButton::RightTrigger2) - trigger(GamepadButton::LeftTrigger2);
+            (Vec2::new(left.x, -left.y), right.y + triggers)
+        });
+        let mut total_pan = Vec2::ZERO;
+        if settings
+            .pan_button
+            .map(|btn| mouse.buttons.pressed(btn))
+            .unwrap_or(false)
+        {
+            total_pan -= total_motion * settings.pan_sensitivity;
+        }
+        if settings.scroll_action == Some(PanOrbitAction::Pan) {
+            total_pan -=
+                total_scroll_lines * settings.scroll_line_sensitivity * settings.pan_sensitivity;
+            total_pan -=
+                total_scroll_pixels * settings.scroll_pixel_sensitivity * settings.pan_sensitivity;
+        }
+        total_pan += key_pan * settings.key_pan_speed * delta;
+        if settings.pan_axis_lock && shortcuts::PAN_AXIS_LOCK.pressed(&keys) {
+            // The axis is chosen by the first movement and kept until the key is released.
+            if state.locked_pan_axis.is_none() && total_pan != Vec2::ZERO {
+                state.locked_pan_axis = Some(if total_pan.x.abs() >= total_pan.y.abs() {
+                    PanAxis::Horizontal
+                } else {
+                    PanAxis::Vertical
+                });
+            }
+            match state.locked_pan_axis {
+                Some(PanAxis::Horizontal) => total_pan.y = 0.0,
+                Some(PanAxis::Vertical) => total_pan.x = 0.0,
+                None => {}
+            }
+        } else {
+            state.locked_pan_axis = None;
+        }
+        let orbit_sensitivity = settings.orbit_sensitivity_at(state.target_radius, planet_radius);
+        let mut total_orbit = Vec2::ZERO;
+        if settings
+            .orbit_button
+            .map(|btn| mouse.buttons.pressed(btn))
+            .unwrap_or(false)
+        {
+            total_orbit -= total_motion * orbit_sensitivity;
+        }
+        if settings.scroll_action == Some(PanOrbitAction::Orbit) {
+            total_orbit -=
+                total_scroll_lines * settings.scroll_line_sensitivity * orbit_sensitivity;
+            total_orbit -=
+                total_scroll_pixels * settings.scroll_pixel_sensitivity * orbit_sensitivity;
+        }
+        total_orbit += (key_orbit + pad_orbit) * settings.key_orbit_speed * delta;
+        let mut total_zoom = Vec2::ZERO;
+        if settings
+            .zoom_button
+            .map(|btn| mouse.buttons.pressed(btn))
+            .unwrap_or(false)
+        {
+            total_zoom -= total_motion * settings.zoom_sensitivity;
+        }
+        if settings.scroll_action == Some(PanOrbitAction::Zoom) {
+            total_zoom -=
+                total_scroll_lines * settings.scroll_line_sensitivity * settings.zoom_sensitivity;
+            total_zoom -=
+                total_scroll_pixels * settings.scroll_pixel_sensitivity * settings.zoom_sensitivity;
+        }
+        total_zoom.y += (key_zoom + pad_zoom) * settings.key_zoom_speed * delta;
+        let mut any = false;
+        if total_zoom != Vec2::ZERO {
+            any = true;
+            state.target_radius = match settings.zoom_curve {
+                ZoomCurve::Exponential => state.target_radius * (-total_zoom.y).exp(),
+                ZoomCurve::Linear => {
+                    let altitude =
+                        (state.target_radius - planet_radius).max(min_radius - planet_radius);
+                    state.target_radius - total_zoom.y * altitude
+                }
+            };
+        }
+        if total_orbit != Vec2::ZERO {
+            any = true;
+            if settings.clamp_pitch {
+                // The clamped view never goes over a pole, so it is never upside down.
+                state.upside_down = false;
+            } else if settings
+                .orbit_button
+                .map(|btn| mouse.buttons.just_pressed(btn))
+                .unwrap_or(false)
+            {
+                state.upside_down =
+                    state.target_pitch < -FRAC_PI_2 || state.target_pitch > FRAC_PI_2;
+            }
+            if state.upside_down {
+                total_orbit.x = -total_orbit.x;
+            }
+            state.target_yaw += total_orbit.x;
+            state.target_pitch += total_orbit.y;
+            if state.target_yaw > PI {
+                state.target_yaw -= TAU;
+            }
+            if state.target_yaw < -PI {
+                state.target_yaw += TAU;
+            }
+        }
+        if total_pan != Vec2::ZERO {
+            any = true;
+            let radius = state.target_radius;
+            let right = transform.rotation * Vec3::X;
+            let up = transform.rotation * Vec3::Y;
+            state.target_center += right * (total_pan.x * radius);
+            state.target_center += up * (total_pan.y * radius);
+            if settings.center_on_surface && state.target_center != Vec3::ZERO {
+                state.target_center = surface_point(state.target_center, &planet).0;
+            }
+        }
+        if any {
+            state.clamp_targets(settings, min_radius, max_radius);
+            // Manual input takes over from any animated move.
+            transition.active = false;
+        }
+    }
+}
+
+/// Ignores stick deflections within `dead_zone` and rescales the rest to start from zero.
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = (length - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON);
+    stick / length * scaled.min(1.0)
+}
+
+/// Eases the camera toward its orbit targets, with an exponential falloff set by the smoothing
+/// time constant.
+fn smooth_camera(
+    time: Res<Time>,
+    mut q_camera: Query<(
+        &PanOrbitSettings,
+        &mut PanOrbitState,
+        &CameraTransition,
+        &mut Transform,
+    )>,
+) {
+    for (settings, mut state, transition, mut transform) in &mut q_camera {
+        if transition.active || state.is_settled() {
+            continue;
+        }
+        let t = if settings.smoothing > 0.0 {
+            1.0 - (-time.delta_secs() / settings.smoothing).exp()
+        } else {
+            1.0
+        };
+        // Turn the short way around, across the wrap at +-PI.
+        let yaw_delta = (state.target_yaw - state.yaw + PI).rem_euclid(TAU) - PI;
+        let close = yaw_delta.abs() < SMOOTHING_EPSILON
+            && (state.target_pitch - state.pitch).abs() < SMOOTHING_EPSILON
+            && (state.target_radius - state.radius).abs() < SMOOTHING_EPSILON
+            && state.center.distance(state.target_center) < SMOOTHING_EPSILON;
+        if t >= 1.0 || close {
+            state.center = state.target_center;
+            state.radius = state.target_radius;
+            state.pitch = state.target_pitch;
+            state.yaw = state.target_yaw;
+        } else {
+            state.center = state.center.lerp(state.target_center, t);
+            state.radius += (state.target_radius - state.radius) * t;
+            state.pitch += (state.target_pitch - state.pitch) * t;
+            state.yaw = (state.yaw + yaw_delta * t + PI).rem_euclid(TAU) - PI;
+        }
+        let rot = Quat::from_euler(EulerRot::YXZ, state.yaw, state.pitch, 0.0);
+        transform.rotation = rot;
+        transform.translation = state.center + rot * Vec3::Z * state.radius;
+    }
+}
+
+/// Switches the camera between perspective projection with the chosen field of view and
+/// orthographic projection, keeping the orthographic view as tall as the perspective one at the
+/// orbit center so zooming still works.
+fn apply_camera_projection(
+    fov: Res<CameraFov>,
+    planet: Res<PlanetSettings>,
+    mut q_camera: Query<(&PanOrbitSettings, &PanOrbitState, &mut Projection)>,
+) {
+    let fov = fov.0;
+    // The clip planes scale with the planet, so a large one isn't cut off by the far plane.
+    let near = PerspectiveProjection::default().near * planet.radius;
+    let far = PerspectiveProjection::default().far * planet.radius;
+    for (settings, state, mut projection) in &mut q_camera {
+        if !settings.orthographic {
+            let up_to_date = matches!(
+                &*projection,
+                Projection::Perspective(perspective)
+                    if perspective.fov == fov && perspective.near == near && perspective.far == far
+            );
+            if up_to_date {
+                continue;
+            }
+            if let Projection::Perspective(perspective) = &mut *projection {
+                perspective.fov = fov;
+                perspective.near = near;
+                perspective.far = far;
+            } else {
+                *projection = Projection::Perspective(PerspectiveProjection {
+                    fov,
+                    near,
+                    far,
+                    ..default()
+                });
+            }
+            continue;
+        }
+        let height = 2.0 * state.radius * (fov / 2.0).tan();
+        let scaling_mode = ScalingMode::FixedVertical {
+            viewport_height: height,
+        };
+        // Only touch the projection when it is out of date, so it isn't marked changed every
+        // frame.
+        let up_to_date = matches!(
+            &*projection,
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical { viewport_height },
+                far: orthographic_far,
+                ..
+            }) if *viewport_height == height && *orthographic_far == far
+        );
+        if up_to_date {
+            continue;
+        }
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scaling_mode = scaling_mode;
+            orthographic.far = far;
+        } else {
+            *projection = Projection::Orthographic(OrthographicProjection {
+                scaling_mode,
+                far,
+                ..OrthographicProjection::default_3d()
+            });
+        }
+    }
+}
+
+fn reset_camera(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    planet: Res<PlanetSettings>,
+    mut q_camera: Query<(&PanOrbitState, &mut CameraTransition)>,
+) {
+    let pad_reset = gamepads
+        .iter()
+        .next()
+        .is_some_and(|gamepad| gamepad.just_pressed(GAMEPAD_RESET_BUTTON));
+    let key_reset = shortcuts::RESET_CAMERA.just_pressed(&keys)
+        && !contexts
+            .ctx_mut()
+            .is_ok_and(|ctx| ctx.wants_keyboard_input());
+    if key_reset || pad_reset {
+        for (state, mut transition) in &mut q_camera {
+            transition.start(*state, PanOrbitState::default_position(planet.radius));
+        }
+    }
+}
+
+/// Toggles spherify when T is pressed, letting the cube-to-sphere change be flipped quickly.
+fn toggle_spherify(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<PlanetSettings>,
+) {
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+    if shortcuts::TOGGLE_SPHERIFY.just_pressed(&keys) && settings.shape == BaseShape::Planet {
+        settings.spherify = !settings.spherify;
+    }
+}
+
+/// Returns the distance from the camera to its near clipping plane.
+fn near_plane(projection: &Projection) -> f32 {
+    match projection {
+        Projection::Perspective(perspective) => perspective.near,
+        Projection::Orthographic(orthographic) => orthographic.near,
+        Projection::Custom(_) => 0.0,
+    }
+}
+
+/// Moves the camera, along the same transition as a reset, to look at the origin from the
+/// distance at which the bounding sphere of the visible faces just fits in the field of view.
+fn frame_planet(
+    mut framing: ResMut<CameraFraming>,
+    fov: Res<CameraFov>,
+    planet: Res<PlanetSettings>,
+    meshes: Res<Assets<Mesh>>,
+    q_faces: Query<(&Mesh3d, &Visibility), With<PlanetFace>>,
+    mut q_camera: Query<(
+        &PanOrbitState,
+        &PanOrbitSettings,
+        &Projection,
+        &mut CameraTransition,
+    )>,
+) {
+    if !framing.pending {
+        return;
+    }
+    framing.pending = false;
+    let bound = q_faces
+        .iter()
+        .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+        .filter_map(|(mesh_3d, _)| meshes.get(&mesh_3d.0))
+        .filter_map(|mesh| match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => Some(positions),
+            _ => None,
+        })
+        .flatten()
+        .map(|&position| Vec3::from(position).length())
+        .fold(0.0, f32::max);
+    if bound <= 0.0 {
+        return;
+    }
+    for (state, settings, projection, mut transition) in &mut q_camera {
+        let aspect_ratio = match projection {
+            Projection::Perspective(perspective) => perspective.aspect_ratio,
+            _ => 1.0,
+        };
+        // The narrower of the vertical and horizontal fields of view limits the fit.
+        let half_fov = fov.0 / 2.0;
+        let half_fov = half_fov.min((half_fov.tan() * aspect_ratio).atan());
+        let min_radius = settings.min_radius * planet.radius;
+        let radius = (bound * FRAME_MARGIN / half_fov.sin()).clamp(
+            min_radius,
+            (settings.max_radius * planet.radius).max(min_radius),
+        );
+        let framed = PanOrbitState {
+            center: Vec3::ZERO,
+            radius,
+            target_center: Vec3::ZERO,
+            target_radius: radius,
+            ..*state
+        };
+        transition.start(*state, framed);
+    }
+}
+
+/// Turns the planet root, and with it the faces and decals, about the Y axis while spin is on,
+/// pausing while the orbit button is held so a particular side can be inspected.
+fn spin_planet(
+    time: Res<Time>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut spin: ResMut<PlanetSpin>,
+    q_camera: Query<&PanOrbitSettings>,
+    mut q_root: Query<&mut Transform, With<PlanetRoot>>,
+) {
+    let orbiting = q_camera.iter().any(|settings| {
+        settings
+            .orbit_button
+            .is_some_and(|button| mouse_buttons.pressed(button))
+    });
+    if spin.enabled && !orbiting {
+        spin.angle = (spin.angle + spin.speed * time.delta_secs()).rem_euclid(TAU);
+    }
+    let rotation = Quat::from_rotation_y(spin.angle);
+    for mut transform in &mut q_root {
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+/// Moves the camera along an active transition using the selected easing curve.
+fn animate_camera_transition(
+    time: Res<Time>,
+    mut q_camera: Query<(
+        &PanOrbitSettings,
+        &mut PanOrbitState,
+        &mut CameraTransition,
+        &mut Transform,
+    )>,
+) {
+    for (settings, mut state, mut transition, mut transform) in &mut q_camera {
+        if !transition.active {
+            continue;
+        }
+        transition.elapsed += time.delta_secs();
+        let t = if settings.reset_duration > 0.0 {
+            (transition.elapsed / settings.reset_duration).min(1.0)
+        } else {
+            1.0
+        };
+        let (from, to) = (transition.from, transition.to);
+        if t >= 1.0 {
+            *state = to;
+            transition.active = false;
+        } else {
+            let e = settings.reset_easing.apply(t);
+            // Turn the short way around, across the wrap at +-PI.
+            let yaw_delta = (to.yaw - from.yaw + PI).rem_euclid(TAU) - PI;
+            state.center = from.center.lerp(to.center, e);
+            state.radius = from.radius + (to.radius - from.radius) * e;
+            state.pitch = from.pitch + (to.pitch - from.pitch) * e;
+            state.yaw = from.yaw + yaw_delta * e;
+            state.upside_down = to.upside_down;
+        }
+        state.settle();
+        let rot = Quat::from_euler(EulerRot::YXZ, state.yaw, state.pitch, 0.0);
+        transform.rotation = rot;
+        transform.translation = state.center + rot * Vec3::Z * state.radius;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Colors given in several spaces, including ones outside sRGB's.
+    fn sample_colors() -> Vec<Color> {
+        vec![
+            Color::BLACK,
+            Color::WHITE,
+            Color::srgb(0.5, 0.5, 0.6),
+            Color::srgba(0.9, 0.1, 0.3, 0.5),
+            Color::linear_rgb(0.02, 0.4, 0.8),
+            Color::hsl(200.0, 0.6, 0.4),
+            Color::oklch(0.7, 0.1, 140.0),
+        ]
+    }
+
+    fn assert_close(a: Color, b: Color) {
+        let (a, b) = (a.to_linear().to_vec4(), b.to_linear().to_vec4());
+        assert!(a.abs_diff_eq(b, 1e-5), "{a} differs from {b}");
+    }
+
+    #[test]
+    fn picker_components_round_trip_in_both_spaces() {
+        for color in sample_colors() {
+            for space in [ColorSpace::Srgb, ColorSpace::Linear] {
+                assert_close(space.color(space.components(color)), color);
+            }
+        }
+    }
+
+    #[test]
+    fn picker_egui_color_round_trips() {
+        for color in sample_colors() {
+            assert_close(from_egui_rgba(to_egui_rgba(color)), color);
+        }
+    }
+
+    /// Returns an app that generates the planet faces and rebuilds them as the settings change,
+    /// without rendering.
+    fn regeneration_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<PlanetSurfaceMaterial>()
+            .init_resource::<PlanetSettings>()
+            .init_resource::<MeshStats>()
+            .init_resource::<CameraFraming>()
+            .init_resource::<AppliedGeometry>()
+            .init_resource::<FaceGeneration>()
+            .init_resource::<TriangleInspector>()
+            .init_resource::<DecalTool>()
+            .init_resource::<HeightBrush>()
+            .init_resource::<FlightPaths>()
+            .init_resource::<ResolutionBuildUp>()
+            .init_resource::<SurfaceArea>()
+            .insert_resource(RegenerationDebounce {
+                delay: 0.0,
+                ..default()
+            })
+            .add_systems(Startup, setup_planet)
+            .add_systems(
+                Update,
+                (apply_planet_settings, finish_face_generation).chain(),
+            );
+        app
+    }
+
+    /// Runs the app until the faces being generated are swapped in and the dropped meshes freed.
+    fn settle(app: &mut App) {
+        for _ in 0..1000 {
+            app.update();
+            if app.world().resource::<FaceGeneration>().0.is_none()
+                && app
+                    .world()
+                    .resource::<RegenerationDebounce>()
+                    .waiting
+                    .is_none()
+            {
+                // Freeing a dropped handle's asset takes another update.
+                app.update();
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("the faces never finished generating");
+    }
+
+    #[test]
+    fn regenerating_frees_the_replaced_meshes() {
+        let mut app = regeneration_app();
+        settle(&mut app);
+        let face_meshes = app.world().resource::<Assets<Mesh>>().len();
+        assert_eq!(face_meshes, FACE_DIRECTIONS.len());
+        // The area is measured from the generated meshes, not the assets.
+        assert!(app.world().resource::<SurfaceArea>().total.is_some());
+
+        for resolution in [12, 20, 6, 31, 12] {
+            app.world_mut().resource_mut::<PlanetSettings>().resolution = resolution;
+            settle(&mut app);
+            let world = app.world_mut();
+            let mut faces = world.query::<&Mesh3d>();
+            let handles: Vec<_> = faces.iter(world).map(|mesh| mesh.0.clone()).collect();
+            let meshes = world.resource::<Assets<Mesh>>();
+            assert_eq!(meshes.len(), face_meshes);
+            for handle in handles {
+                let mesh = meshes.get(&handle).expect("the face mesh is live");
+                assert_eq!(mesh.count_vertices(), (resolution * resolution) as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn clamping_limits_the_orbit_targets() {
+        let settings = PanOrbitSettings {
+            clamp_pitch: true,
+            ..default()
+        };
+        let mut state = PanOrbitState::default_position(1.0);
+        state.target_pitch = PI;
+        state.target_radius = 100.0;
+        state.clamp_targets(&settings, 1.5, 12.0);
+        assert_eq!(state.target_pitch, settings.max_pitch);
+        assert_eq!(state.target_radius, 12.0);
+        // The eased values are left to follow the targets.
+        assert_eq!(state.pitch, 0.0);
+
+        state.target_pitch = -PI;
+        state.target_radius = 0.5;
+        state.clamp_targets(&settings, 1.5, 12.0);
+        assert_eq!(state.target_pitch, settings.min_pitch);
+        assert_eq!(state.target_radius, 1.5);
+
+        let free = PanOrbitSettings {
+            clamp_pitch: false,
+            ..default()
+        };
+        state.target_pitch = PI;
+        state.clamp_targets(&free, 1.5, 12.0);
+        assert_eq!(state.target_pitch, PI);
+    }
+}
@@ -0,0 +1,277 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+
+use crate::{ColorSpace, PanOrbitState, PlanetSettings, color_picker_widget, persistence};
+
+/// The on-screen diameter of the light direction widget, in logical pixels.
+const WIDGET_SIZE: f32 = 120.0;
+
+/// The ambient brightness lighting the night side until it is changed in the UI.
+const DEFAULT_AMBIENT_BRIGHTNESS: f32 = 2000.0;
+
+/// The configuration of one directional light shining on the planet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightConfig {
+    /// The unit vector pointing from the planet towards the light.
+    pub direction: Vec3,
+    #[serde(with = "persistence::srgba")]
+    pub color: Color,
+    pub illuminance: f32,
+    pub shadows: bool,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(-1.0, 0.5, 1.0).normalize(),
+            color: Color::WHITE,
+            illuminance: 2000.0,
+            shadows: false,
+        }
+    }
+}
+
+/// A resource holding every directional light in the scene.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lights {
+    pub entries: Vec<LightConfig>,
+}
+
+impl Default for Lights {
+    fn default() -> Self {
+        let sun = Quat::from_euler(EulerRot::XYZ, -PI / 4.0, -PI / 4.0, 0.0);
+        Self {
+            entries: vec![LightConfig {
+                direction: sun * Vec3::Z,
+                illuminance: 5000.0,
+                shadows: true,
+                ..default()
+            }],
+        }
+    }
+}
+
+/// A component to identify a directional light and the index of its entry in [`Lights`].
+#[derive(Component)]
+struct SceneLight(usize);
+
+/// A resource tracking the light edited in the UI and whether its handle is being dragged.
+#[derive(Resource)]
+struct LightWidget {
+    selected: usize,
+    dragging: bool,
+    ambient_enabled: bool,
+    /// The ambient brightness restored when ambient light is enabled again.
+    ambient_brightness: f32,
+}
+
+impl Default for LightWidget {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            dragging: false,
+            ambient_enabled: true,
+            ambient_brightness: DEFAULT_AMBIENT_BRIGHTNESS,
+        }
+    }
+}
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AmbientLight {
+            color: Color::WHITE,
+            brightness: DEFAULT_AMBIENT_BRIGHTNESS,
+            ..default()
+        })
+        .init_resource::<Lights>()
+        .init_resource::<LightWidget>()
+        .add_systems(Update, (sync_lights, draw_light_direction))
+        .add_systems(EguiPrimaryContextPass, ui_light_widget);
+    }
+}
+
+/// Returns a transform that makes a directional light shine from `direction` towards the planet.
+fn light_transform(direction: Vec3) -> Transform {
+    let up = if direction.y.abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+    Transform::default().looking_to(-direction, up)
+}
+
+/// Respawns the light entities when lights are added or removed, and updates them otherwise.
+fn sync_lights(
+    mut commands: Commands,
+    lights: Res<Lights>,
+    mut q_lights: Query<(Entity, &SceneLight, &mut DirectionalLight, &mut Transform)>,
+) {
+    if !lights.is_changed() {
+        return;
+    }
+    if q_lights.iter().count() != lights.entries.len() {
+        for (entity, ..) in &q_lights {
+            commands.entity(entity).despawn();
+        }
+        for (index, config) in lights.entries.iter().enumerate() {
+            commands.spawn((
+                DirectionalLight {
+                    color: config.color,
+                    illuminance: config.illuminance,
+                    shadows_enabled: config.shadows,
+                    ..default()
+                },
+                light_transform(config.direction),
+                SceneLight(index),
+            ));
+        }
+        return;
+    }
+    for (_, marker, mut light, mut transform) in &mut q_lights {
+        let Some(config) = lights.entries.get(marker.0) else {
+            continue;
+        };
+        light.color = config.color;
+        light.illuminance = config.illuminance;
+        light.shadows_enabled = config.shadows;
+        transform.set_if_neq(light_transform(config.direction));
+    }
+}
+
+/// Lists the lights for adding, removing and editing them, with a lighting-sphere handle:
+/// dragging on the disc sets the direction the selected light shines from, as seen from
+/// the current camera.
+fn ui_light_widget(
+    mut contexts: EguiContexts,
+    mut widget: ResMut<LightWidget>,
+    mut lights: ResMut<Lights>,
+    mut ambient: ResMut<AmbientLight>,
+    q_camera: Query<&Transform, With<PanOrbitState>>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    let Ok(camera_transform) = q_camera.single() else {
+        return;
+    };
+    // Loading or recovering the lights can leave fewer than the selection expects.
+    let last = lights.entries.len().saturating_sub(1);
+    if widget.selected > last {
+        widget.selected = last;
+    }
+
+    egui::Window::new("Light")
+        .default_open(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut widget.ambient_enabled, "Ambient");
+                ui.add_enabled(
+                    widget.ambient_enabled,
+                    egui::Slider::new(&mut widget.ambient_brightness, 0.0..=5000.0),
+                );
+            })
+            .response
+            .on_hover_text("Turn ambient light down to let the night side go dark.");
+            let brightness = if widget.ambient_enabled {
+                widget.ambient_brightness
+            } else {
+                0.0
+            };
+            if ambient.brightness != brightness {
+                ambient.brightness = brightness;
+            }
+
+            ui.separator();
+
+            ui.horizontal_wrapped(|ui| {
+                for index in 0..lights.entries.len() {
+                    ui.selectable_value(
+                        &mut widget.selected,
+                        index,
+                        format!("Light {}", index + 1),
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Add Light").clicked() {
+                    lights.entries.push(LightConfig::default());
+                    widget.selected = lights.entries.len() - 1;
+                }
+                if ui
+                    .add_enabled(
+                        widget.selected < lights.entries.len(),
+                        egui::Button::new("Remove"),
+                    )
+                    .clicked()
+                {
+                    lights.entries.remove(widget.selected);
+                    widget.selected = widget.selected.saturating_sub(1);
+                }
+            });
+            widget.dragging = false;
+            let Some(config) = lights.entries.get_mut(widget.selected) else {
+                ui.label("No directional lights.");
+                return;
+            };
+
+            ui.separator();
+            color_picker_widget(ui, &mut config.color, ColorSpace::Srgb);
+            ui.add(
+                egui::Slider::new(&mut config.illuminance, 0.0..=20000.0).text("Illuminance (lux)"),
+            );
+            ui.checkbox(&mut config.shadows, "Cast Shadows");
+
+            ui.label("Drag to set the light direction.");
+            let (response, painter) =
+                ui.allocate_painter(egui::Vec2::splat(WIDGET_SIZE), egui::Sense::drag());
+            let rect = response.rect;
+            let radius = rect.width() * 0.5;
+
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let offset = (pointer - rect.center()) / radius;
+                let mut disc = Vec2::new(offset.x, -offset.y);
+                if disc.length() > 1.0 {
+                    disc = disc.normalize();
+                }
+                let z = (1.0 - disc.length_squared()).max(0.0).sqrt();
+                let view_direction = Vec3::new(disc.x, disc.y, z);
+                config.direction = camera_transform.rotation * view_direction;
+            }
+            widget.dragging = response.dragged();
+
+            let visuals = ui.visuals();
+            painter.circle_filled(rect.center(), radius, visuals.extreme_bg_color);
+            painter.circle_stroke(rect.center(), radius, visuals.widgets.inactive.fg_stroke);
+
+            let view_direction = camera_transform.rotation.inverse() * config.direction;
+            let handle = rect.center() + egui::vec2(view_direction.x, -view_direction.y) * radius;
+            if view_direction.z >= 0.0 {
+                painter.circle_filled(handle, 6.0, egui::Color32::YELLOW);
+            } else {
+                // The light is behind the planet from this viewpoint.
+                painter.circle_stroke(handle, 6.0, egui::Stroke::new(2.0, egui::Color32::YELLOW));
+            }
+        });
+}
+
+/// Draws an arrow from the planet towards the selected light while its handle is dragged.
+fn draw_light_direction(
+    mut gizmos: Gizmos,
+    widget: Res<LightWidget>,
+    lights: Res<Lights>,
+    settings: Res<PlanetSettings>,
+) {
+    if !widget.dragging {
+        return;
+    }
+    if let Some(config) = lights.entries.get(widget.selected) {
+        let direction = config.direction * settings.radius;
+        gizmos.arrow(direction * 1.2, direction * 2.0, Color::srgb(1.0, 0.9, 0.2));
+    }
+}
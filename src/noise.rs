@@ -0,0 +1,254 @@
+use std::f32::consts::FRAC_1_PI;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Hashes a lattice point to a pseudo-random value in `[0, 1)`.
+fn hash(p: Vec3) -> f32 {
+    let q = (p * FRAC_1_PI + Vec3::new(0.1, 0.2, 0.3)).fract_gl() * 17.0;
+    let h = q.x * q.y * q.z * (q.x + q.y + q.z);
+    h - h.floor()
+}
+
+/// How far apart, along each axis, different seeds sample the noise.
+const SEED_RANGE: f32 = 256.0;
+
+/// Returns the offset the given seed shifts noise sampling by, so each seed samples an
+/// unrelated region of the same noise field.
+pub fn seed_offset(seed: u32) -> Vec3 {
+    // A PCG hash, stepped once per axis.
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_mul(747_796_405).wrapping_add(2_891_336_453);
+        let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277_803_737);
+        ((word >> 22) ^ word) as f32 / u32::MAX as f32
+    };
+    Vec3::new(next(), next(), next()) * SEED_RANGE
+}
+
+/// How far, in noise lattice cells, the full variation shifts where the noise is sampled.
+const VARIATION_RANGE: f32 = 1.0;
+
+/// Returns [`seed_offset`] nudged along a direction the seed also picks, by `variation` from
+/// 0 to 1, so stepping the variation walks through planets close to the seed's.
+pub fn varied_seed_offset(seed: u32, variation: f32) -> Vec3 {
+    let walk = (seed_offset(seed ^ 0x5bd1_e995) / SEED_RANGE * 2.0 - 1.0).normalize_or(Vec3::X);
+    seed_offset(seed) + walk * variation.clamp(0.0, 1.0) * VARIATION_RANGE
+}
+
+/// Counts the random seeds handed out, so clicks within one clock tick still differ.
+static SEED_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Returns a fresh seed from a xorshift generator started from the clock.
+pub fn random_seed() -> u32 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+    let count = SEED_COUNTER.fetch_add(1, Ordering::Relaxed);
+    // Xorshift has a fixed point at zero, so the state must never be zero.
+    let mut state = (nanos ^ count.wrapping_mul(0x9e37_79b9)) | 1;
+    for _ in 0..4 {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+    }
+    state
+}
+
+/// Trilinearly interpolated value noise in `[0, 1]`.
+pub fn value_noise(p: Vec3) -> f32 {
+    let i = p.floor();
+    let f = p - i;
+    let u = f * f * (3.0 - 2.0 * f);
+    let corner = |x: f32, y: f32, z: f32| hash(i + Vec3::new(x, y, z));
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    lerp(
+        lerp(
+            lerp(corner(0.0, 0.0, 0.0), corner(1.0, 0.0, 0.0), u.x),
+            lerp(corner(0.0, 1.0, 0.0), corner(1.0, 1.0, 0.0), u.x),
+            u.y,
+        ),
+        lerp(
+            lerp(corner(0.0, 0.0, 1.0), corner(1.0, 0.0, 1.0), u.x),
+            lerp(corner(0.0, 1.0, 1.0), corner(1.0, 1.0, 1.0), u.x),
+            u.y,
+        ),
+        u.z,
+    )
+}
+
+/// Sums octaves of value noise, each at twice the frequency and half the amplitude of the last,
+/// normalized back to `[0, 1]`.
+pub fn fbm(p: Vec3, octaves: u32) -> f32 {
+    fractal_noise(p, octaves, 2.0, 0.5)
+}
+
+/// Sums octaves of value noise, each at `lacunarity` times the frequency and `persistence`
+/// times the amplitude of the last, normalized back to `[0, 1]`.
+pub fn fractal_noise(p: Vec3, octaves: u32, lacunarity: f32, persistence: f32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut total = 0.0;
+    let mut point = p;
+    for _ in 0..octaves {
+        sum += value_noise(point) * amplitude;
+        total += amplitude;
+        amplitude *= persistence;
+        point *= lacunarity;
+    }
+    sum / f32::max(total, 1e-6)
+}
+
+/// Sums octaves of ridged value noise, `offset - |noise|` squared, with each octave weighted by
+/// `gain` times the one before, so ridges grow sharper detail while valleys stay smooth.
+/// Normalized back to `[0, 1]`.
+pub fn ridged_multifractal(
+    p: Vec3,
+    octaves: u32,
+    lacunarity: f32,
+    persistence: f32,
+    offset: f32,
+    gain: f32,
+) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut total = 0.0;
+    let mut weight = 1.0;
+    let mut point = p;
+    for _ in 0..octaves {
+        let ridge = (offset - (value_noise(point) * 2.0 - 1.0).abs()).max(0.0);
+        let signal = ridge * ridge * weight;
+        weight = (signal * gain).clamp(0.0, 1.0);
+        sum += signal * amplitude;
+        total += amplitude;
+        amplitude *= persistence;
+        point *= lacunarity;
+    }
+    sum / f32::max(total * offset * offset, 1e-6)
+}
+
+/// How a noise layer shapes its fractal noise into heights.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseMode {
+    /// The noise as is, for rolling hills.
+    #[default]
+    Simple,
+    /// One minus the absolute noise, squared, for sharp mountain ridges.
+    Ridged,
+    /// Ridged noise whose octaves are weighted by the one before, for mountain ranges with
+    /// detailed peaks and smooth valleys; shaped by the layer's ridge offset and gain.
+    RidgedMultifractal,
+}
+
+/// One layer of fractal noise displacing the planet surface.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseLayer {
+    pub enabled: bool,
+    /// Leaves the layer out of the surface for now, keeping its settings.
+    pub mute: bool,
+    /// While any layer is soloed, only the soloed layers shape the surface, muted or not.
+    pub solo: bool,
+    pub mode: NoiseMode,
+    /// How many features of the first octave span the unit sphere along each axis.
+    pub frequency: f32,
+    /// How far the layer raises or lowers the surface, relative to the planet radius.
+    pub amplitude: f32,
+    pub octaves: u32,
+    /// The frequency multiplier from one octave to the next.
+    pub lacunarity: f32,
+    /// The amplitude multiplier from one octave to the next.
+    pub persistence: f32,
+    /// Raises the ridges of multifractal noise; higher values flatten the valleys between them.
+    pub ridge_offset: f32,
+    /// How strongly each multifractal octave is weighted by the one before.
+    pub ridge_gain: f32,
+}
+
+impl Default for NoiseLayer {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mute: false,
+            solo: false,
+            mode: NoiseMode::Simple,
+            frequency: 2.0,
+            amplitude: 0.05,
+            octaves: 4,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            ridge_offset: 1.0,
+            ridge_gain: 2.0,
+        }
+    }
+}
+
+impl NoiseLayer {
+    /// Returns the layer's displacement at the given unit direction, in
+    /// `[-amplitude, amplitude]` for simple noise and `[0, amplitude]` for ridges.
+    ///
+    /// `offset` shifts where the noise is sampled; see [`seed_offset`].
+    pub fn height(&self, direction: Vec3, offset: Vec3) -> f32 {
+        let point = direction * self.frequency + offset;
+        if self.mode == NoiseMode::RidgedMultifractal {
+            return ridged_multifractal(
+                point,
+                self.octaves,
+                self.lacunarity,
+                self.persistence,
+                self.ridge_offset,
+                self.ridge_gain,
+            ) * self.amplitude;
+        }
+        let noise =
+            fractal_noise(point, self.octaves, self.lacunarity, self.persistence) * 2.0 - 1.0;
+        let shaped = match self.mode {
+            NoiseMode::Simple => noise,
+            NoiseMode::Ridged | NoiseMode::RidgedMultifractal => (1.0 - noise.abs()).powi(2),
+        };
+        shaped * self.amplitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ridged_multifractal_stays_normalized() {
+        for i in 0..500 {
+            let p = Vec3::new(i as f32 * 0.37, i as f32 * 0.11, i as f32 * -0.23);
+            for (offset, gain) in [(0.5, 0.0), (1.0, 2.0), (1.5, 4.0)] {
+                let value = ridged_multifractal(p, 6, 2.0, 0.5, offset, gain);
+                assert!(
+                    (0.0..=1.0).contains(&value),
+                    "{value} at {p} for {offset}, {gain}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ridged_multifractal_gain_weights_later_octaves() {
+        let p = Vec3::new(1.3, -0.7, 2.1);
+        // Without gain every octave after the first is weighted to nothing.
+        let first_octave = ridged_multifractal(p, 1, 2.0, 0.5, 1.0, 0.0);
+        let no_gain = ridged_multifractal(p, 6, 2.0, 0.5, 1.0, 0.0);
+        assert!((no_gain * 63.0 / 32.0 - first_octave).abs() < 1e-5);
+        assert_ne!(ridged_multifractal(p, 6, 2.0, 0.5, 1.0, 2.0), no_gain);
+    }
+
+    #[test]
+    fn variation_walks_steadily_away_from_the_seed() {
+        for seed in [0, 1, 99, u32::MAX] {
+            assert_eq!(varied_seed_offset(seed, 0.0), seed_offset(seed));
+            let step = varied_seed_offset(seed, 0.1).distance(varied_seed_offset(seed, 0.2));
+            assert!((step - 0.1 * VARIATION_RANGE).abs() < 1e-3, "{step}");
+            let full = varied_seed_offset(seed, 1.0).distance(seed_offset(seed));
+            assert!((full - VARIATION_RANGE).abs() < 1e-3, "{full}");
+            assert_eq!(varied_seed_offset(seed, 2.0), varied_seed_offset(seed, 1.0));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::PlanetSettings;
+
+/// The subdivisions of the ocean icosphere, fine enough to look round at close range.
+const OCEAN_SUBDIVISIONS: u32 = 6;
+
+const OCEAN_COLOR: Color = Color::srgba(0.1, 0.3, 0.7, 0.6);
+
+/// A component to identify the translucent sphere drawn at sea level.
+#[derive(Component)]
+struct Ocean;
+
+pub struct OceanPlugin;
+
+impl Plugin for OceanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_ocean)
+            .add_systems(Update, apply_ocean_settings);
+    }
+}
+
+fn setup_ocean(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = match Sphere::new(1.0).mesh().ico(OCEAN_SUBDIVISIONS) {
+        Ok(mesh) => mesh,
+        Err(err) => {
+            error!("Failed to build the ocean sphere: {err}");
+            return;
+        }
+    };
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: OCEAN_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.2,
+            ..default()
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+        Ocean,
+    ));
+}
+
+/// Scales the ocean to the sea level on the planet radius and shows it only on the planet when enabled.
+fn apply_ocean_settings(
+    settings: Res<PlanetSettings>,
+    mut q_ocean: Query<(&mut Transform, &mut Visibility), With<Ocean>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let visible = settings.show_ocean && settings.shape.is_planet();
+    for (mut transform, mut visibility) in &mut q_ocean {
+        transform.set_if_neq(Transform::from_scale(Vec3::splat(
+            settings.ocean_level * settings.radius,
+        )));
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+use serde::{Deserialize, Serialize};
+
+use crate::lighting::Lights;
+use crate::{CameraBookmarks, MIN_RESOLUTION, PlanetSettings};
+
+/// The file written when settings are saved explicitly.
+pub const SETTINGS_PATH: &str = "planet_settings.ron";
+
+/// The file periodically written by auto-save as a safety net.
+pub const RECOVERY_PATH: &str = "planet_settings.recovery.ron";
+
+/// An event asking for the settings to be written to or read back from [`SETTINGS_PATH`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFileRequest {
+    Save,
+    Load,
+}
+
+/// A resource to hold the auto-save configuration and what was last written.
+#[derive(Resource, Debug)]
+pub struct AutoSave {
+    pub enabled: bool,
+    pub interval_secs: f32,
+    timer: Timer,
+    last_saved: Option<String>,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        let interval_secs = 30.0;
+        Self {
+            enabled: true,
+            interval_secs,
+            timer: Timer::from_seconds(interval_secs, TimerMode::Repeating),
+            last_saved: None,
+        }
+    }
+}
+
+/// Everything written to a settings file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSettings {
+    pub planet: PlanetSettings,
+    #[serde(default)]
+    pub lights: Lights,
+    #[serde(default)]
+    pub bookmarks: CameraBookmarks,
+}
+
+/// A resource present while a recovery file from a previous session can be restored.
+#[derive(Resource)]
+struct PendingRecovery(SavedSettings);
+
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SettingsFileRequest>()
+            .init_resource::<AutoSave>()
+            .add_systems(Startup, detect_recovery)
+            .add_systems(Update, (handle_settings_file_requests, auto_save_settings))
+            .add_systems(EguiPrimaryContextPass, recovery_prompt);
+    }
+}
+
+/// Serializes settings to a RON string.
+pub fn settings_to_ron(settings: &SavedSettings) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default())
+}
+
+/// Reads settings from a RON file, filling missing fields with their defaults.
+pub fn load_settings(path: impl AsRef<Path>) -> Result<SavedSettings, String> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut saved = parse_settings(&contents).map_err(|e| format!("{}: {e}", path.display()))?;
+    if saved.planet.resolution < MIN_RESOLUTION {
+        warn!(
+            "{}: resolution {} is too low, using {MIN_RESOLUTION}",
+            path.display(),
+            saved.planet.resolution
+        );
+        saved.planet.resolution = MIN_RESOLUTION;
+    }
+    Ok(saved)
+}
+
+/// Parses the contents of a settings file in either the current or the older format.
+fn parse_settings(contents: &str) -> Result<SavedSettings, ron::error::SpannedError> {
+    ron::from_str(contents).or_else(|err| {
+        // Files written before lights were saved hold only the planet settings.
+        ron::from_str(contents)
+            .map(|planet| SavedSettings {
+                planet,
+                lights: Lights::default(),
+                bookmarks: CameraBookmarks::default(),
+            })
+            .map_err(|_| err)
+    })
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Offers the recovery file if it is newer than the last explicit save.
+fn detect_recovery(mut commands: Commands) {
+    let Some(recovered_at) = modified(RECOVERY_PATH) else {
+        return;
+    };
+    if modified(SETTINGS_PATH).is_some_and(|saved_at| saved_at >= recovered_at) {
+        return;
+    }
+    match load_settings(RECOVERY_PATH) {
+        Ok(settings) => commands.insert_resource(PendingRecovery(settings)),
+        Err(err) => warn!("Ignoring unreadable recovery file {err}"),
+    }
+}
+
+/// Saves the settings to, or replaces them with, the explicit settings file; the faces are
+/// regenerated from loaded settings like from any other change.
+fn handle_settings_file_requests(
+    mut requests: EventReader<SettingsFileRequest>,
+    mut settings: ResMut<PlanetSettings>,
+    mut lights: ResMut<Lights>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+) {
+    for request in requests.read() {
+        match request {
+            SettingsFileRequest::Save => {
+                let saved = SavedSettings {
+                    planet: settings.clone(),
+                    lights: lights.clone(),
+                    bookmarks: bookmarks.clone(),
+                };
+                let result = settings_to_ron(&saved)
+                    .map_err(|err| err.to_string())
+                    .and_then(|contents| {
+                        fs::write(SETTINGS_PATH, contents).map_err(|err| err.to_string())
+                    });
+                match result {
+                    Ok(()) => info!("Saved settings to {SETTINGS_PATH}"),
+                    Err(err) => error!("Failed to save {SETTINGS_PATH}: {err}"),
+                }
+            }
+            SettingsFileRequest::Load => match load_settings(SETTINGS_PATH) {
+                Ok(saved) => {
+                    *settings = saved.planet;
+                    *lights = saved.lights;
+                    *bookmarks = saved.bookmarks;
+                    info!("Loaded settings from {SETTINGS_PATH}");
+                }
+                Err(err) => error!("Failed to load settings from {err}"),
+            },
+        }
+    }
+}
+
+/// Writes the settings to the recovery file every interval if they differ from the last write.
+fn auto_save_settings(
+    time: Res<Time>,
+    settings: Res<PlanetSettings>,
+    lights: Res<Lights>,
+    bookmarks: Res<CameraBookmarks>,
+    mut auto_save: ResMut<AutoSave>,
+) {
+    if !auto_save.enabled {
+        return;
+    }
+    let interval = std::time::Duration::from_secs_f32(auto_save.interval_secs.max(1.0));
+    if auto_save.timer.duration() != interval {
+        auto_save.timer.set_duration(interval);
+    }
+    if !auto_save.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let saved = SavedSettings {
+        planet: settings.clone(),
+        lights: lights.clone(),
+        bookmarks: bookmarks.clone(),
+    };
+    let contents = match settings_to_ron(&saved) {
+        Ok(contents) => contents,
+        Err(err) => {
+            error!("Failed to serialize settings for auto-save: {err}");
+            return;
+        }
+    };
+    if auto_save.last_saved.as_ref() == Some(&contents) {
+        return;
+    }
+    match fs::write(RECOVERY_PATH, &contents) {
+        Ok(()) => auto_save.last_saved = Some(contents),
+        Err(err) => error!("Failed to write {RECOVERY_PATH}: {err}"),
+    }
+}
+
+/// Asks whether to restore the settings found in the recovery file.
+fn recovery_prompt(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    pending: Option<Res<PendingRecovery>>,
+    mut settings: ResMut<PlanetSettings>,
+    mut lights: ResMut<Lights>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+) {
+    let Some(pending) = pending else { return };
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Restore Settings")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label("Auto-saved settings from a previous session were found.");
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    *settings = pending.0.planet.clone();
+                    *lights = pending.0.lights.clone();
+                    *bookmarks = pending.0.bookmarks.clone();
+                    commands.remove_resource::<PendingRecovery>();
+                }
+                if ui.button("Discard").clicked() {
+                    commands.remove_resource::<PendingRecovery>();
+                }
+            });
+        });
+}
+
+/// Serializes a [`Color`] as its sRGBA components.
+pub mod srgba {
+    use bevy::color::{Color, ColorToComponents, Srgba};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        Srgba::from(*color).to_f32_array().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let [r, g, b, a] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Color::srgba(r, g, b, a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Colored(#[serde(with = "srgba")] Color);
+
+    #[test]
+    fn colors_round_trip_through_srgba() {
+        let colors = [
+            Color::srgb(0.5, 0.5, 0.6),
+            Color::srgba(0.95, 0.97, 1.0, 0.25),
+            Color::linear_rgb(0.02, 0.4, 0.8),
+            Color::hsl(200.0, 0.6, 0.4),
+            Color::oklch(0.7, 0.1, 140.0),
+        ];
+        for color in colors {
+            let ron = ron::to_string(&Colored(color)).unwrap();
+            let Colored(read) = ron::from_str(&ron).unwrap();
+            let (expected, read) = (color.to_linear().to_vec4(), read.to_linear().to_vec4());
+            assert!(
+                read.abs_diff_eq(expected, 1e-5),
+                "{ron} read as {read}, not {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn resolutions_below_the_minimum_load_as_the_minimum() {
+        for resolution in [0, 1] {
+            let saved = SavedSettings {
+                planet: PlanetSettings {
+                    resolution,
+                    ..default()
+                },
+                lights: Lights::default(),
+                bookmarks: CameraBookmarks::default(),
+            };
+            let path = std::env::temp_dir().join(format!(
+                "bevy_mesh_{}_resolution_{resolution}.ron",
+                std::process::id()
+            ));
+            fs::write(&path, settings_to_ron(&saved).unwrap()).unwrap();
+            let loaded = load_settings(&path);
+            fs::remove_file(&path).unwrap();
+
+            let planet = loaded.unwrap().planet;
+            assert_eq!(planet.resolution, MIN_RESOLUTION);
+            let mesh = crate::generation::create_face_mesh(
+                planet.resolution,
+                Vec3::Y,
+                true,
+                0.0,
+                1.0,
+                planet.normal_shading,
+                |_| 0.0,
+            )
+            .unwrap();
+            assert_eq!(mesh.count_vertices(), 4);
+            assert_eq!(mesh.indices().map(|indices| indices.len()), Some(6));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use bevy::ecs::system::SystemParam;
+use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, MeshRayCastSettings, RayMeshHit};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::{PanOrbitState, PlanetFace};
+
+/// A system parameter for casting the cursor ray against the planet faces.
+#[derive(SystemParam)]
+pub struct PlanetPicker<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<PanOrbitState>>,
+    faces: Query<'w, 's, &'static GlobalTransform, With<PlanetFace>>,
+    ray_cast: MeshRayCast<'w, 's>,
+}
+
+impl PlanetPicker<'_, '_> {
+    /// Returns the world-space ray under the cursor, if the cursor is inside the window.
+    pub fn cursor_ray(&self) -> Option<Ray3d> {
+        let window = self.windows.single().ok()?;
+        let cursor = window.cursor_position()?;
+        let (camera, camera_transform) = self.cameras.single().ok()?;
+        camera.viewport_to_world(camera_transform, cursor).ok()
+    }
+
+    /// Returns the nearest planet face hit by the cursor ray.
+    pub fn cursor_hit(&mut self) -> Option<(Entity, RayMeshHit)> {
+        let ray = self.cursor_ray()?;
+        let faces = &self.faces;
+        let filter = |entity| faces.contains(entity);
+        let settings = MeshRayCastSettings::default().with_filter(&filter);
+        self.ray_cast.cast_ray(ray, &settings).first().cloned()
+    }
+
+    /// Returns the point under the cursor in the planet's own space, undoing any spin.
+    pub fn cursor_point(&mut self) -> Option<Vec3> {
+        let (entity, hit) = self.cursor_hit()?;
+        let transform = self.faces.get(entity).ok()?;
+        Some(transform.affine().inverse().transform_point3(hit.point))
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+
+use crate::elevation::ElevationColors;
+use crate::ice_caps::IceCaps;
+use crate::noise::{NoiseLayer, NoiseMode};
+use crate::{BaseShape, PlanetSettings};
+
+/// A curated combination of settings for a familiar kind of planet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// Whatever the settings are; choosing it changes nothing.
+    #[default]
+    Custom,
+    Earth,
+    Moon,
+    Mars,
+}
+
+impl Preset {
+    /// Every preset, in the order the editor lists them.
+    pub const ALL: [Preset; 4] = [Preset::Custom, Preset::Earth, Preset::Moon, Preset::Mars];
+
+    /// Returns the preset the settings were filled with, or `Custom` once they are edited.
+    pub fn of(settings: &PlanetSettings) -> Self {
+        let Some(current) = Look::of(settings) else {
+            return Preset::Custom;
+        };
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.look().as_ref() == Some(&current))
+            .unwrap_or(Preset::Custom)
+    }
+
+    /// Overwrites the settings that shape and color the planet with the preset's, leaving the
+    /// resolution and the display settings as they are.
+    pub fn apply(self, settings: &mut PlanetSettings) {
+        if let Some(look) = self.look() {
+            look.write(settings);
+        }
+    }
+
+    fn look(self) -> Option<Look> {
+        let layer = |mode, frequency, amplitude, octaves| NoiseLayer {
+            mode,
+            frequency,
+            amplitude,
+            octaves,
+            ..default()
+        };
+        match self {
+            Preset::Custom => None,
+            Preset::Earth => Some(Look {
+                flattening: 0.0034,
+                seed: 1,
+                noise_layers: vec![
+                    layer(NoiseMode::Simple, 1.5, 0.04, 6),
+                    layer(NoiseMode::Ridged, 4.0, 0.015, 4),
+                ],
+                color: Color::srgb(0.3, 0.5, 0.3),
+                roughness: 0.7,
+                show_ocean: true,
+                elevation_colors: ElevationColors {
+                    enabled: true,
+                    ..default()
+                },
+                ice_caps: IceCaps {
+                    enabled: true,
+                    ..default()
+                },
+            }),
+            Preset::Moon => Some(Look {
+                flattening: 0.0012,
+                seed: 7,
+                noise_layers: vec![
+                    layer(NoiseMode::Ridged, 3.0, 0.04, 6),
+                    layer(NoiseMode::Simple, 8.0, 0.01, 4),
+                ],
+                color: Color::srgb(0.55, 0.55, 0.55),
+                roughness: 0.95,
+                show_ocean: false,
+                elevation_colors: ElevationColors::default(),
+                ice_caps: IceCaps::default(),
+            }),
+            // There is no crater noise, so fine ridges stand in for the crater rims.
+            Preset::Mars => Some(Look {
+                flattening: 0.0059,
+                seed: 4,
+                noise_layers: vec![
+                    layer(NoiseMode::Simple, 2.0, 0.03, 5),
+                    layer(NoiseMode::Ridged, 6.0, 0.01, 3),
+                ],
+                color: Color::srgb(0.7, 0.35, 0.2),
+                roughness: 0.9,
+                show_ocean: false,
+                elevation_colors: ElevationColors::default(),
+                ice_caps: IceCaps {
+                    enabled: true,
+                    latitude: 80.0,
+                    thickness: 0.005,
+                    ..default()
+                },
+            }),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Preset::Custom => "Custom",
+            Preset::Earth => "Earth",
+            Preset::Moon => "Moon",
+            Preset::Mars => "Mars",
+        }
+    }
+}
+
+/// The settings a preset chooses; applying one also makes the base shape a spherified,
+/// uneroded, unsculpted and only radially displaced planet without the procedural texture.
+#[derive(Debug, Clone, PartialEq)]
+struct Look {
+    flattening: f32,
+    seed: u32,
+    noise_layers: Vec<NoiseLayer>,
+    color: Color,
+    roughness: f32,
+    show_ocean: bool,
+    elevation_colors: ElevationColors,
+    ice_caps: IceCaps,
+}
+
+impl Look {
+    /// Returns the settings' look, or `None` when they aren't a plain planet any preset makes.
+    fn of(settings: &PlanetSettings) -> Option<Self> {
+        let plain = settings.shape == BaseShape::Planet
+            && settings.spherify
+            && settings.metallic == 0.0
+            && settings.ocean_level == 1.0
+            && !settings.texture.enabled
+            && !settings.erosion.enabled
+            && settings.lateral_strength == 0.0
+            && settings.variation == 0.0
+            && settings.sculpt.stamps.is_empty();
+        plain.then(|| Self {
+            flattening: settings.flattening,
+            seed: settings.seed,
+            noise_layers: settings.noise_layers.clone(),
+            color: settings.color,
+            roughness: settings.roughness,
+            show_ocean: settings.show_ocean,
+            elevation_colors: settings.elevation_colors.clone(),
+            ice_caps: settings.ice_caps.clone(),
+        })
+    }
+
+    fn write(self, settings: &mut PlanetSettings) {
+        settings.shape = BaseShape::Planet;
+        settings.spherify = true;
+        settings.flattening = self.flattening;
+        settings.seed = self.seed;
+        settings.variation = 0.0;
+        settings.noise_layers = self.noise_layers;
+        settings.color = self.color;
+        settings.metallic = 0.0;
+        settings.roughness = self.roughness;
+        settings.texture.enabled = false;
+        settings.erosion.enabled = false;
+        settings.lateral_strength = 0.0;
+        settings.show_ocean = self.show_ocean;
+        settings.ocean_level = 1.0;
+        settings.elevation_colors = self.elevation_colors;
+        settings.ice_caps = self.ice_caps;
+        settings.sculpt.stamps.clear();
+    }
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::generation::{face_axes, polar_scale};
+use crate::{BaseShape, FACE_DIRECTIONS, PlanetSettings};
+
+/// How far the preview grid floats above the surface so the planet does not hide it.
+const PREVIEW_LIFT: f32 = 1.003;
+
+/// The most segments used to draw a single grid line, regardless of resolution.
+const MAX_LINE_SEGMENTS: u32 = 64;
+
+/// A resource holding a resolution that is previewed as a grid before being applied.
+#[derive(Resource, Debug)]
+pub struct ResolutionPreview {
+    pub enabled: bool,
+    pub target: u32,
+    pub color: Color,
+}
+
+impl Default for ResolutionPreview {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: PlanetSettings::default().resolution,
+            color: Color::srgba(1.0, 1.0, 1.0, 0.25),
+        }
+    }
+}
+
+pub struct ResolutionPreviewPlugin;
+
+impl Plugin for ResolutionPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ResolutionPreview>()
+            .add_systems(Update, draw_resolution_preview);
+    }
+}
+
+/// Draws the grid lines of the pending resolution on every face until it is applied.
+fn draw_resolution_preview(
+    mut gizmos: Gizmos,
+    preview: Res<ResolutionPreview>,
+    settings: Res<PlanetSettings>,
+) {
+    if !preview.enabled || preview.target == settings.resolution || preview.target < 2 {
+        return;
+    }
+
+    if settings.shape == BaseShape::Plane {
+        let (axis_a, axis_b) = face_axes(Vec3::Y);
+        let lift = Vec3::Y * (PREVIEW_LIFT - 1.0);
+        draw_grid(&mut gizmos, &preview, |percent| {
+            (((percent.x - 0.5) * axis_a + (percent.y - 0.5) * axis_b) * settings.plane_size + lift)
+                * settings.radius
+        });
+        return;
+    }
+
+    let scale = polar_scale(settings.flattening);
+    if matches!(settings.shape, BaseShape::UvSphere | BaseShape::Icosphere) {
+        // Bevy generates the spheres, so there is no grid to preview.
+        return;
+    }
+
+    for normal in FACE_DIRECTIONS {
+        let (axis_a, axis_b) = face_axes(normal);
+        draw_grid(&mut gizmos, &preview, |percent| {
+            let point_on_unit_cube =
+                normal + (percent.x - 0.5) * 2.0 * axis_a + (percent.y - 0.5) * 2.0 * axis_b;
+            let point = if settings.spherify {
+                point_on_unit_cube.normalize()
+            } else {
+                point_on_unit_cube
+            };
+            point * scale * settings.radius * PREVIEW_LIFT
+        });
+    }
+}
+
+/// Draws the preview grid lines of one face, placing points from their position across it.
+fn draw_grid(gizmos: &mut Gizmos, preview: &ResolutionPreview, point: impl Fn(Vec2) -> Vec3) {
+    let segments = (preview.target - 1).min(MAX_LINE_SEGMENTS);
+    for i in 0..preview.target {
+        let t = i as f32 / (preview.target - 1) as f32;
+        let along = (0..=segments).map(|s| s as f32 / segments as f32);
+        gizmos.linestrip(along.clone().map(|s| point(Vec2::new(t, s))), preview.color);
+        gizmos.linestrip(along.map(|s| point(Vec2::new(s, t))), preview.color);
+    }
+}
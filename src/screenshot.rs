@@ -0,0 +1,356 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::{
+    RenderApp,
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    gpu_readback::{Readback, ReadbackComplete},
+    render_asset::{RenderAssetUsages, RenderAssets},
+    render_graph::{
+        NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+    },
+    render_resource::{
+        BufferUsages, DownlevelFlags, Extent3d, Origin3d, TexelCopyBufferInfo,
+        TexelCopyBufferLayout, TexelCopyTextureInfo, TextureAspect, TextureDimension,
+        TextureFormat,
+    },
+    renderer::{RenderAdapter, RenderContext, RenderDevice},
+    storage::{GpuShaderStorageBuffer, ShaderStorageBuffer},
+    view::{
+        ViewDepthTexture,
+        screenshot::{Screenshot, save_to_disk},
+    },
+};
+use bevy_egui::{EguiPostUpdateSet, EguiRenderOutput, PrimaryEguiContext};
+
+/// The bytes of one texel of the depth buffer, a 32-bit float.
+const DEPTH_TEXEL_BYTES: usize = 4;
+
+/// The gray of the farthest surface in a depth image, so it stands apart from the black sky.
+const FARTHEST_GRAY: f32 = 0.2;
+
+/// An event asking for the rendered planet to be saved as a PNG, without the editor UI.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScreenshotRequest;
+
+/// A resource to hold where screenshots are written.
+#[derive(Resource, Debug)]
+pub struct ScreenshotSettings {
+    pub directory: String,
+    /// Whether the depth buffer is saved next to each screenshot, as a grayscale PNG.
+    ///
+    /// Multisampled depth can't be copied out, so multisampling is off while this is on.
+    pub depth: bool,
+    /// Whether the UI is left out of the frame being rendered, because it is being captured.
+    hide_ui: bool,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            directory: ".".to_string(),
+            depth: false,
+            hide_ui: false,
+        }
+    }
+}
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScreenshotRequest>()
+            .init_resource::<ScreenshotSettings>()
+            .add_plugins(ExtractComponentPlugin::<DepthReadback>::default())
+            .add_systems(Update, (sync_depth_msaa, take_screenshot))
+            .add_systems(
+                PostUpdate,
+                hide_ui_for_screenshot.after(EguiPostUpdateSet::ProcessOutput),
+            );
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<DepthCopyNode>>(Core3d, DepthCopyLabel)
+            .add_render_graph_edges(Core3d, (Node3d::EndMainPass, DepthCopyLabel));
+    }
+}
+
+/// A component on the camera whose depth buffer is copied into the storage buffer each frame,
+/// until the copy has been read back.
+#[derive(Component, ExtractComponent, Clone)]
+struct DepthReadback(Handle<ShaderStorageBuffer>);
+
+#[derive(RenderLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct DepthCopyLabel;
+
+/// Copies the depth buffer of cameras with a [`DepthReadback`] once the main pass is drawn.
+#[derive(Default)]
+struct DepthCopyNode;
+
+impl ViewNode for DepthCopyNode {
+    type ViewQuery = (&'static ViewDepthTexture, &'static DepthReadback);
+
+    fn run<'w>(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (depth, readback): QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        if !depth_copies_supported(world.resource::<RenderAdapter>()) {
+            return Ok(());
+        }
+        let buffers = world.resource::<RenderAssets<GpuShaderStorageBuffer>>();
+        let Some(buffer) = buffers.get(&readback.0) else {
+            return Ok(());
+        };
+        let size = depth.texture.size();
+        let row_bytes = depth_row_bytes(size.width);
+        if depth.texture.sample_count() != 1
+            || buffer.buffer.size() < row_bytes as u64 * u64::from(size.height)
+        {
+            return Ok(());
+        }
+        render_context.command_encoder().copy_texture_to_buffer(
+            TexelCopyTextureInfo {
+                texture: &depth.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::DepthOnly,
+            },
+            TexelCopyBufferInfo {
+                buffer: &buffer.buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(row_bytes as u32),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        Ok(())
+    }
+}
+
+/// Returns whether the GPU can copy depth textures into buffers, which WebGL and some OpenGL
+/// drivers can't.
+fn depth_copies_supported(adapter: &RenderAdapter) -> bool {
+    adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES)
+}
+
+/// Returns the bytes each row of a depth buffer `width` texels wide takes once copied out,
+/// padded as the GPU requires.
+fn depth_row_bytes(width: u32) -> usize {
+    RenderDevice::align_copy_bytes_per_row(width as usize * DEPTH_TEXEL_BYTES)
+}
+
+/// Turns multisampling off while depth capture is on, so the depth buffer can be copied.
+fn sync_depth_msaa(
+    settings: Res<ScreenshotSettings>,
+    mut cameras: Query<&mut Msaa, With<Camera3d>>,
+) {
+    let wanted = if settings.depth {
+        Msaa::Off
+    } else {
+        Msaa::default()
+    };
+    for mut msaa in &mut cameras {
+        if *msaa != wanted {
+            *msaa = wanted;
+        }
+    }
+}
+
+/// Returns the timestamped file a screenshot taken now is written to.
+fn screenshot_path(directory: &str) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    PathBuf::from(directory).join(format!("planet_{millis}.png"))
+}
+
+/// Returns the file the depth buffer is written to next to the screenshot at `path`.
+fn depth_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}_depth.png"))
+}
+
+/// Captures the primary window this frame, which is rendered without the UI, and its depth
+/// buffer if asked to.
+fn take_screenshot(
+    mut commands: Commands,
+    mut requests: EventReader<ScreenshotRequest>,
+    mut settings: ResMut<ScreenshotSettings>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    cameras: Query<(Entity, &Camera), With<Camera3d>>,
+    adapter: Option<Res<RenderAdapter>>,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+    if let Err(err) = fs::create_dir_all(&settings.directory) {
+        error!("Failed to create {}: {err}", settings.directory);
+        return;
+    }
+    let path = screenshot_path(&settings.directory);
+    // Saving logs the path once the frame has been read back.
+    if settings.depth && !adapter.is_some_and(|adapter| depth_copies_supported(&adapter)) {
+        warn!("Skipping the depth capture, which this GPU backend can't copy out");
+    } else if settings.depth {
+        capture_depth(&mut commands, &mut buffers, &cameras, depth_path(&path));
+    }
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path));
+    settings.hide_ui = true;
+}
+
+/// Copies the camera's depth buffer this frame and writes it to `path` once it is read back.
+fn capture_depth(
+    commands: &mut Commands,
+    buffers: &mut Assets<ShaderStorageBuffer>,
+    cameras: &Query<(Entity, &Camera), With<Camera3d>>,
+    path: PathBuf,
+) {
+    let Ok((camera, view)) = cameras.single() else {
+        warn!("Skipping the depth capture, which needs exactly one 3D camera");
+        return;
+    };
+    let Some(size) = view.physical_target_size() else {
+        warn!("Skipping the depth capture of a camera without a render target");
+        return;
+    };
+    let row_bytes = depth_row_bytes(size.x);
+    let mut buffer = ShaderStorageBuffer::with_size(
+        row_bytes * size.y as usize,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    buffer.buffer_description.usage |= BufferUsages::COPY_DST | BufferUsages::COPY_SRC;
+    let buffer = buffers.add(buffer);
+    commands
+        .entity(camera)
+        .insert(DepthReadback(buffer.clone()));
+    commands.spawn(Readback::buffer(buffer)).observe(
+        move |trigger: Trigger<ReadbackComplete>, mut commands: Commands| {
+            // Later frames' copies are dropped along with the readback.
+            commands.entity(trigger.target()).despawn();
+            commands.entity(camera).remove::<DepthReadback>();
+            save_depth_image(&trigger.0, size, row_bytes, &path);
+        },
+    );
+}
+
+/// Writes the depth buffer read back as `data` to a grayscale PNG at `path`.
+fn save_depth_image(data: &[u8], size: UVec2, row_bytes: usize, path: &Path) {
+    let Some(pixels) = depth_pixels(data, size, row_bytes) else {
+        warn!(
+            "Skipping {}: nothing was drawn into the depth buffer",
+            path.display()
+        );
+        return;
+    };
+    let image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::R8Unorm,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    match image.try_into_dynamic() {
+        Ok(image) => match image.save(path) {
+            Ok(()) => info!("Depth saved to {}", path.display()),
+            Err(err) => error!("Failed to save {}: {err}", path.display()),
+        },
+        Err(err) => error!("Failed to convert the depth buffer: {err}"),
+    }
+}
+
+/// Converts depth rows of `row_bytes` each into grayscale, nearer brighter, stretched over the
+/// range of the drawn surfaces; nothing drawn, at depth 0 with reversed Z, stays black.
+///
+/// Returns `None` if nothing was drawn.
+fn depth_pixels(data: &[u8], size: UVec2, row_bytes: usize) -> Option<Vec<u8>> {
+    let row_len = size.x as usize * DEPTH_TEXEL_BYTES;
+    let depths: Vec<f32> = data
+        .chunks(row_bytes)
+        .take(size.y as usize)
+        .flat_map(|row| row[..row_len.min(row.len())].chunks_exact(DEPTH_TEXEL_BYTES))
+        .map(|texel| f32::from_le_bytes([texel[0], texel[1], texel[2], texel[3]]))
+        .collect();
+    let drawn = || depths.iter().copied().filter(|&depth| depth > 0.0);
+    let nearest = drawn().reduce(f32::max)?;
+    let farthest = drawn().fold(nearest, f32::min);
+    let range = (nearest - farthest).max(f32::EPSILON);
+    let pixels = depths
+        .iter()
+        .map(|&depth| {
+            if depth <= 0.0 {
+                return 0;
+            }
+            let nearness = (depth - farthest) / range;
+            ((FARTHEST_GRAY + (1.0 - FARTHEST_GRAY) * nearness) * 255.0).round() as u8
+        })
+        .collect();
+    Some(pixels)
+}
+
+/// Drops the UI's paint jobs for the frame being captured, so only the scene is saved.
+fn hide_ui_for_screenshot(
+    mut settings: ResMut<ScreenshotSettings>,
+    mut q_output: Query<&mut EguiRenderOutput, With<PrimaryEguiContext>>,
+) {
+    if !settings.hide_ui {
+        return;
+    }
+    settings.hide_ui = false;
+    for mut output in &mut q_output {
+        output.paint_jobs.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_is_stretched_over_the_drawn_surfaces() {
+        let size = UVec2::new(3, 2);
+        let row_bytes = depth_row_bytes(size.x);
+        let mut data = vec![0xAB; row_bytes * size.y as usize];
+        let depths: [[f32; 3]; 2] = [[0.0, 0.5, 0.25], [0.125, 0.5, 0.0]];
+        for (row, depths) in depths.iter().enumerate() {
+            for (column, depth) in depths.iter().enumerate() {
+                let start = row * row_bytes + column * DEPTH_TEXEL_BYTES;
+                data[start..start + DEPTH_TEXEL_BYTES].copy_from_slice(&depth.to_le_bytes());
+            }
+        }
+        let farthest = (FARTHEST_GRAY * 255.0).round() as u8;
+        let middle = ((FARTHEST_GRAY + (1.0 - FARTHEST_GRAY) / 3.0) * 255.0).round() as u8;
+        assert_eq!(
+            depth_pixels(&data, size, row_bytes),
+            Some(vec![0, 255, middle, farthest, 255, 0])
+        );
+        assert_eq!(depth_pixels(&vec![0; data.len()], size, row_bytes), None);
+    }
+
+    #[test]
+    fn depth_is_saved_next_to_the_screenshot() {
+        let path = Path::new("shots").join("planet_42.png");
+        assert_eq!(
+            depth_path(&path),
+            Path::new("shots").join("planet_42_depth.png")
+        );
+    }
+}
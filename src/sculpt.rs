@@ -0,0 +1,197 @@
+use bevy::input::mouse::MouseButton;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_egui::EguiContexts;
+use serde::{Deserialize, Serialize};
+
+use crate::PlanetSettings;
+use crate::picking::PlanetPicker;
+use crate::shortcuts;
+
+/// The smallest brush radius; narrower stamps would have no falloff to divide by.
+pub const MIN_BRUSH_RADIUS: f32 = 0.02;
+
+/// How far apart stamps are laid along a stroke, relative to the brush radius.
+const STAMP_SPACING: f32 = 0.25;
+
+/// How far from its center a stamp is evaluated, in multiples of its falloff width.
+const STAMP_CUTOFF_SIGMAS: f32 = 3.0;
+
+/// The step used to estimate the slope of the height layer when tilting normals.
+const SLOPE_EPSILON: f32 = 1e-3;
+
+/// One dab of the elevation brush, raising or lowering the surface around a point.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeightStamp {
+    /// The unit vector from the planet center to the middle of the stamp.
+    pub center: Vec3,
+    /// The brush radius, as a distance on the unit sphere.
+    pub radius: f32,
+    /// The height added at the center, relative to the planet radius; negative lowers.
+    pub amount: f32,
+}
+
+/// The hand-painted elevation added on top of the generated surface.
+///
+/// Stamps are stored rather than per-vertex offsets so the layer survives resolution changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HeightLayer {
+    pub stamps: Vec<HeightStamp>,
+}
+
+impl HeightLayer {
+    /// Returns the painted height in the given unit direction, with a Gaussian falloff per stamp.
+    pub fn height(&self, direction: Vec3) -> f32 {
+        self.stamps
+            .iter()
+            .map(|stamp| {
+                let sigma = stamp.radius.max(MIN_BRUSH_RADIUS) * 0.5;
+                let distance = direction.distance(stamp.center);
+                if distance > sigma * STAMP_CUTOFF_SIGMAS {
+                    return 0.0;
+                }
+                stamp.amount * (-(distance * distance) / (2.0 * sigma * sigma)).exp()
+            })
+            .sum()
+    }
+}
+
+/// Raises the mesh's vertices by the painted height and tilts their normals along its slope.
+pub fn apply_height_layer(mesh: &mut Mesh, layer: &HeightLayer) {
+    if layer.stamps.is_empty() {
+        return;
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let mut directions = Vec::with_capacity(positions.len());
+    for position in positions.iter_mut() {
+        let point = Vec3::from(*position);
+        let direction = point.normalize_or_zero();
+        *position = (point * (1.0 + layer.height(direction))).into();
+        directions.push(direction);
+    }
+
+    let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        return;
+    };
+    for (normal, direction) in normals.iter_mut().zip(directions) {
+        let (tangent, bitangent) = direction.any_orthonormal_pair();
+        let height = layer.height(direction);
+        let slope = |axis: Vec3| {
+            (layer.height((direction + axis * SLOPE_EPSILON).normalize()) - height) / SLOPE_EPSILON
+        };
+        let tilted = Vec3::from(*normal) - tangent * slope(tangent) - bitangent * slope(bitangent);
+        *normal = tilted.normalize_or(Vec3::from(*normal)).into();
+    }
+}
+
+/// A resource to hold the settings of the elevation brush.
+#[derive(Resource, Debug)]
+pub struct HeightBrush {
+    pub enabled: bool,
+    pub radius: f32,
+    /// The height each stamp adds at its center, relative to the planet radius.
+    pub strength: f32,
+    /// Where the current stroke last laid a stamp.
+    last_stamp: Option<Vec3>,
+}
+
+impl Default for HeightBrush {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 0.2,
+            strength: 0.005,
+            last_stamp: None,
+        }
+    }
+}
+
+pub struct SculptPlugin;
+
+impl Plugin for SculptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeightBrush>()
+            .add_systems(Update, paint_heights);
+    }
+}
+
+/// Lays stamps along the cursor while the brush key and left mouse button are held.
+fn paint_heights(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut brush: ResMut<HeightBrush>,
+    mut settings: ResMut<PlanetSettings>,
+    mut picker: PlanetPicker,
+) {
+    if !brush.enabled
+        || !settings.shape.is_planet()
+        || !shortcuts::SCULPT.pressed(&keys)
+        || !mouse_buttons.pressed(MouseButton::Left)
+    {
+        brush.last_stamp = None;
+        return;
+    }
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_pointer_input()
+    {
+        return;
+    }
+    let Some(point) = picker.cursor_point() else {
+        return;
+    };
+    let center = point.normalize();
+    if brush
+        .last_stamp
+        .is_some_and(|last| last.distance(center) < brush.radius * STAMP_SPACING)
+    {
+        return;
+    }
+    let sign = if shortcuts::SCULPT_LOWER.pressed(&keys) {
+        -1.0
+    } else {
+        1.0
+    };
+    settings.sculpt.stamps.push(HeightStamp {
+        center,
+        radius: brush.radius.max(MIN_BRUSH_RADIUS),
+        amount: brush.strength * sign,
+    });
+    brush.last_stamp = Some(center);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stamps_without_a_radius_stay_finite() {
+        let center = Vec3::Y;
+        let layer = HeightLayer {
+            stamps: vec![HeightStamp {
+                center,
+                radius: 0.0,
+                amount: 0.01,
+            }],
+        };
+        let narrowest = HeightLayer {
+            stamps: vec![HeightStamp {
+                radius: MIN_BRUSH_RADIUS,
+                ..layer.stamps[0]
+            }],
+        };
+        for direction in [center, Vec3::new(0.005, 1.0, 0.0).normalize(), Vec3::X] {
+            let height = layer.height(direction);
+            assert!(height.is_finite());
+            assert_eq!(height, narrowest.height(direction));
+        }
+        assert_eq!(layer.height(center), 0.01);
+    }
+}
@@ -0,0 +1,42 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+struct BarycentricWireframe {
+    color: vec4<f32>,
+    width: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: BarycentricWireframe;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) barycentric: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) barycentric: vec3<f32>,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(
+        get_world_from_local(vertex.instance_index),
+        vec4<f32>(vertex.position, 1.0),
+    );
+    out.barycentric = vertex.barycentric;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Distance to the nearest edge in pixels, so lines keep their width at any mesh density.
+    let pixels = in.barycentric / max(fwidth(in.barycentric), vec3<f32>(1e-6));
+    let distance = min(min(pixels.x, pixels.y), pixels.z);
+    let coverage = 1.0 - smoothstep(material.width * 0.5 - 0.5, material.width * 0.5 + 0.5, distance);
+    if coverage <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(material.color.rgb, material.color.a * coverage);
+}
@@ -0,0 +1,99 @@
+#import bevy_pbr::{
+    mesh_functions::get_local_from_world,
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+struct ProceduralSurface {
+    color: vec4<f32>,
+    scale: f32,
+    octaves: u32,
+    strength: f32,
+    backface_color: vec4<f32>,
+}
+
+@group(2) @binding(100) var<uniform> surface: ProceduralSurface;
+
+fn hash(p: vec3<f32>) -> f32 {
+    let q = fract(p * 0.3183099 + vec3<f32>(0.1, 0.2, 0.3)) * 17.0;
+    return fract(q.x * q.y * q.z * (q.x + q.y + q.z));
+}
+
+// Trilinearly interpolated value noise in [0, 1].
+fn value_noise(p: vec3<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    return mix(
+        mix(
+            mix(hash(i + vec3<f32>(0.0, 0.0, 0.0)), hash(i + vec3<f32>(1.0, 0.0, 0.0)), u.x),
+            mix(hash(i + vec3<f32>(0.0, 1.0, 0.0)), hash(i + vec3<f32>(1.0, 1.0, 0.0)), u.x),
+            u.y,
+        ),
+        mix(
+            mix(hash(i + vec3<f32>(0.0, 0.0, 1.0)), hash(i + vec3<f32>(1.0, 0.0, 1.0)), u.x),
+            mix(hash(i + vec3<f32>(0.0, 1.0, 1.0)), hash(i + vec3<f32>(1.0, 1.0, 1.0)), u.x),
+            u.y,
+        ),
+        u.z,
+    );
+}
+
+// Sums octaves of noise, each at twice the frequency and half the amplitude of the last.
+fn fbm(p: vec3<f32>, octaves: u32) -> f32 {
+    var sum = 0.0;
+    var amplitude = 0.5;
+    var total = 0.0;
+    var point = p;
+    for (var i = 0u; i < octaves; i++) {
+        sum += value_noise(point) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        point *= 2.0;
+    }
+    return sum / max(total, 1e-6);
+}
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+
+    if surface.strength > 0.0 {
+        // Sampled in the mesh's own space, so the pattern turns and scales with the planet.
+        let local_position = (get_local_from_world(in.instance_index) * in.world_position).xyz;
+        let n = fbm(local_position * surface.scale, surface.octaves);
+        let t = smoothstep(0.3, 0.7, n) * surface.strength;
+        let base = pbr_input.material.base_color;
+        pbr_input.material.base_color = vec4<f32>(mix(base.rgb, surface.color.rgb, t), base.a);
+    }
+
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    if !is_front && surface.backface_color.a > 0.0 {
+        out.color = surface.backface_color;
+    }
+#endif
+
+    return out;
+}
@@ -0,0 +1,35 @@
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::{lights, view},
+}
+
+struct ToonMaterial {
+    color: vec4<f32>,
+    outline_color: vec4<f32>,
+    bands: u32,
+    outline_width: f32,
+}
+
+@group(2) @binding(0) var<uniform> material: ToonMaterial;
+
+// How bright the unlit side stays, so its shape still reads.
+const SHADOW_FLOOR: f32 = 0.15;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let to_eye = normalize(view.world_position - in.world_position.xyz);
+
+    // Surfaces seen edge-on form the silhouette outline.
+    if dot(normal, to_eye) < material.outline_width {
+        return material.outline_color;
+    }
+
+    var intensity = 0.0;
+    for (var i = 0u; i < lights.n_directional_lights; i += 1u) {
+        intensity = max(intensity, dot(normal, lights.directional_lights[i].direction_to_light));
+    }
+    let bands = f32(max(material.bands, 1u));
+    let banded = ceil(clamp(intensity, 0.0, 1.0) * bands) / bands;
+    return vec4<f32>(material.color.rgb * mix(SHADOW_FLOOR, 1.0, banded), material.color.a);
+}
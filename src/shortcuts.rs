@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+/// A keyboard binding and what it does, as listed in the help overlay.
+pub struct Shortcut {
+    pub keys: &'static [KeyCode],
+    /// Whether the action lasts while the key is held rather than firing once.
+    pub hold: bool,
+    pub action: &'static str,
+}
+
+impl Shortcut {
+    /// Returns whether any of the shortcut's keys was pressed this frame.
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        input.any_just_pressed(self.keys.iter().copied())
+    }
+
+    /// Returns whether any of the shortcut's keys is held down.
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        input.any_pressed(self.keys.iter().copied())
+    }
+
+    /// Returns the names of the shortcut's keys, e.g. "F1 / /", listing keys that share a name,
+    /// such as the left and right Shift, once.
+    pub(crate) fn label(&self) -> String {
+        let mut labels: Vec<String> = Vec::new();
+        for &key in self.keys {
+            let label = key_label(key);
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        labels.join(" / ")
+    }
+}
+
+pub const RESET_CAMERA: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyR],
+    hold: false,
+    action: "Reset the camera",
+};
+
+pub const PAN_AXIS_LOCK: Shortcut = Shortcut {
+    keys: &[KeyCode::ShiftLeft, KeyCode::ShiftRight],
+    hold: true,
+    action: "Pan along one axis",
+};
+
+pub const DOLLY_IN: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyW],
+    hold: true,
+    action: "Move the camera closer",
+};
+
+pub const DOLLY_OUT: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyS],
+    hold: true,
+    action: "Move the camera away",
+};
+
+pub const ORBIT_LEFT: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyA],
+    hold: true,
+    action: "Orbit left",
+};
+
+pub const ORBIT_RIGHT: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyD],
+    hold: true,
+    action: "Orbit right",
+};
+
+pub const ORBIT_UP: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyE],
+    hold: true,
+    action: "Orbit up",
+};
+
+pub const ORBIT_DOWN: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyQ],
+    hold: true,
+    action: "Orbit down",
+};
+
+pub const PAN_LEFT: Shortcut = Shortcut {
+    keys: &[KeyCode::ArrowLeft],
+    hold: true,
+    action: "Pan left",
+};
+
+pub const PAN_RIGHT: Shortcut = Shortcut {
+    keys: &[KeyCode::ArrowRight],
+    hold: true,
+    action: "Pan right",
+};
+
+pub const PAN_UP: Shortcut = Shortcut {
+    keys: &[KeyCode::ArrowUp],
+    hold: true,
+    action: "Pan up",
+};
+
+pub const PAN_DOWN: Shortcut = Shortcut {
+    keys: &[KeyCode::ArrowDown],
+    hold: true,
+    action: "Pan down",
+};
+
+pub const TOGGLE_SPHERIFY: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyT],
+    hold: false,
+    action: "Toggle spherify",
+};
+
+pub const SCULPT: Shortcut = Shortcut {
+    keys: &[KeyCode::KeyB],
+    hold: true,
+    action: "Paint elevation by dragging with the left button",
+};
+
+pub const SCULPT_LOWER: Shortcut = Shortcut {
+    keys: &[KeyCode::ControlLeft, KeyCode::ControlRight],
+    hold: true,
+    action: "Lower the surface while painting elevation",
+};
+
+pub const TOGGLE_HELP: Shortcut = Shortcut {
+    keys: &[KeyCode::F1, KeyCode::Slash],
+    hold: false,
+    action: "Show or hide this help",
+};
+
+/// Every shortcut, in the order the help overlay lists them.
+pub const SHORTCUTS: [&Shortcut; 16] = [
+    &RESET_CAMERA,
+    &DOLLY_IN,
+    &DOLLY_OUT,
+    &ORBIT_LEFT,
+    &ORBIT_RIGHT,
+    &ORBIT_UP,
+    &ORBIT_DOWN,
+    &PAN_LEFT,
+    &PAN_RIGHT,
+    &PAN_UP,
+    &PAN_DOWN,
+    &PAN_AXIS_LOCK,
+    &TOGGLE_SPHERIFY,
+    &SCULPT,
+    &SCULPT_LOWER,
+    &TOGGLE_HELP,
+];
+
+/// A resource toggling the keyboard shortcut help overlay.
+#[derive(Resource, Default, Debug)]
+pub struct ShortcutHelp {
+    pub visible: bool,
+}
+
+pub struct ShortcutsPlugin;
+
+impl Plugin for ShortcutsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShortcutHelp>()
+            .add_systems(Update, toggle_help)
+            .add_systems(EguiPrimaryContextPass, ui_shortcut_help);
+    }
+}
+
+/// Returns the name shown for a key, e.g. "R" for `KeyCode::KeyR`.
+pub(crate) fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Slash => "/".to_string(),
+        KeyCode::ShiftLeft | KeyCode::ShiftRight => "Shift".to_string(),
+        KeyCode::ControlLeft | KeyCode::ControlRight => "Ctrl".to_string(),
+        _ => {
+            let name = format!("{key:?}");
+            name.strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Arrow"))
+                .map(str::to_string)
+                .unwrap_or(name)
+        }
+    }
+}
+
+fn toggle_help(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut help: ResMut<ShortcutHelp>,
+) {
+    if let Ok(ctx) = contexts.ctx_mut()
+        && ctx.wants_keyboard_input()
+    {
+        return;
+    }
+    if TOGGLE_HELP.just_pressed(&keys) {
+        help.visible = !help.visible;
+    }
+}
+
+/// Lists every registered shortcut in a window over the middle of the screen.
+fn ui_shortcut_help(mut contexts: EguiContexts, mut help: ResMut<ShortcutHelp>) {
+    if !help.visible {
+        return;
+    }
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    egui::Window::new("Keyboard Shortcuts")
+        .open(&mut help.visible)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+                for shortcut in SHORTCUTS {
+                    let keys = shortcut.label();
+                    if shortcut.hold {
+                        ui.monospace(format!("Hold {keys}"));
+                    } else {
+                        ui.monospace(keys);
+                    }
+                    ui.label(shortcut.action);
+                    ui.end_row();
+                }
+            });
+        });
+}
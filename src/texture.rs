@@ -0,0 +1,186 @@
+use bevy::asset::{LoadState, embedded_asset};
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use serde::{Deserialize, Serialize};
+
+use crate::{PlanetMaterial, PlanetSettings, material_base_color, persistence};
+
+/// The shader blending fractal noise into the base color of the planet.
+const PROCEDURAL_SHADER_PATH: &str = "embedded://bevy_mesh/shaders/procedural_surface.wgsl";
+
+/// The unlit color of back faces while they are highlighted.
+const BACKFACE_COLOR: LinearRgba = LinearRgba::rgb(1.0, 0.0, 0.6);
+
+/// The planet material: a standard material with a procedural noise texture evaluated per pixel.
+pub type PlanetSurfaceMaterial = ExtendedMaterial<StandardMaterial, ProceduralSurface>;
+
+/// The settings of the procedural surface texture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProceduralTexture {
+    pub enabled: bool,
+    /// How many noise features fit across the planet radius.
+    pub scale: f32,
+    pub octaves: u32,
+    /// How far the noise blends from the base color towards `color`.
+    pub strength: f32,
+    #[serde(with = "persistence::srgba")]
+    pub color: Color,
+}
+
+impl Default for ProceduralTexture {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scale: 4.0,
+            octaves: 4,
+            strength: 0.6,
+            color: Color::srgb(0.25, 0.35, 0.2),
+        }
+    }
+}
+
+impl ProceduralTexture {
+    /// Returns the material extension drawing this texture on a planet of the given radius.
+    pub fn extension(&self, radius: f32) -> ProceduralSurface {
+        ProceduralSurface {
+            color: self.color.into(),
+            scale: self.scale / radius.max(f32::EPSILON),
+            octaves: self.octaves,
+            strength: if self.enabled { self.strength } else { 0.0 },
+            backface_color: LinearRgba::NONE,
+        }
+    }
+}
+
+/// An event asking to change the image wrapped around the planet as its base color.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum BaseColorTextureRequest {
+    /// Loads the image at [`BaseColorTexture::path`].
+    Load,
+    /// Goes back to the flat base color.
+    Clear,
+}
+
+/// A resource to hold the image file wrapped around the planet as its base color.
+#[derive(Resource, Debug, Default)]
+pub struct BaseColorTexture {
+    /// The file to load, either absolute or relative to the assets folder.
+    pub path: String,
+    /// The image still loading, until it can go on the material.
+    loading: Option<Handle<Image>>,
+    /// The file of the image on the material.
+    applied: Option<String>,
+}
+
+impl BaseColorTexture {
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn applied(&self) -> Option<&str> {
+        self.applied.as_deref()
+    }
+}
+
+/// A material extension that blends fractal noise into the base color, resolution-independently.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct ProceduralSurface {
+    #[uniform(100)]
+    pub color: LinearRgba,
+    /// How many noise features fit in one unit of the mesh's local space.
+    #[uniform(100)]
+    pub scale: f32,
+    #[uniform(100)]
+    pub octaves: u32,
+    #[uniform(100)]
+    pub strength: f32,
+    /// The unlit color of back faces; fully transparent leaves them shaded normally.
+    #[uniform(100)]
+    pub backface_color: LinearRgba,
+}
+
+impl ProceduralSurface {
+    /// Draws back faces in a flat, contrasting color when `enabled`, whatever the surface coloring.
+    pub fn with_backface_highlight(mut self, enabled: bool) -> Self {
+        self.backface_color = if enabled {
+            BACKFACE_COLOR
+        } else {
+            LinearRgba::NONE
+        };
+        self
+    }
+}
+
+impl MaterialExtension for ProceduralSurface {
+    fn fragment_shader() -> ShaderRef {
+        PROCEDURAL_SHADER_PATH.into()
+    }
+}
+
+pub struct ProceduralTexturePlugin;
+
+impl Plugin for ProceduralTexturePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/procedural_surface.wgsl");
+        app.add_plugins(MaterialPlugin::<PlanetSurfaceMaterial>::default())
+            .add_event::<BaseColorTextureRequest>()
+            .init_resource::<BaseColorTexture>()
+            .add_systems(Update, update_base_color_texture);
+    }
+}
+
+/// Starts loading or clears the base color texture, and puts a loaded image on the planet
+/// material with a white base color so it shows unmodulated.
+fn update_base_color_texture(
+    mut requests: EventReader<BaseColorTextureRequest>,
+    asset_server: Res<AssetServer>,
+    mut texture: ResMut<BaseColorTexture>,
+    settings: Res<PlanetSettings>,
+    planet_material: Res<PlanetMaterial>,
+    mut materials: ResMut<Assets<PlanetSurfaceMaterial>>,
+) {
+    for request in requests.read() {
+        match request {
+            BaseColorTextureRequest::Load => {
+                let path = texture.path.trim().to_string();
+                if path.is_empty() {
+                    warn!("No texture file given");
+                    continue;
+                }
+                // Files outside the assets folder are allowed, as the path is typed by the user.
+                texture.loading = Some(asset_server.load_override(path));
+            }
+            BaseColorTextureRequest::Clear => {
+                texture.loading = None;
+                texture.applied = None;
+                if let Some(material) = materials.get_mut(&planet_material.0) {
+                    material.base.base_color_texture = None;
+                    material.base.base_color = material_base_color(&settings, false);
+                }
+            }
+        }
+    }
+
+    let Some(handle) = &texture.loading else {
+        return;
+    };
+    let path = texture.path.trim().to_string();
+    match asset_server.load_state(handle) {
+        LoadState::Loaded => {
+            if let Some(material) = materials.get_mut(&planet_material.0) {
+                material.base.base_color_texture = Some(handle.clone());
+                material.base.base_color = material_base_color(&settings, true);
+            }
+            info!("Loaded base color texture {path}");
+            texture.loading = None;
+            texture.applied = Some(path);
+        }
+        LoadState::Failed(err) => {
+            error!("Failed to load texture {path}: {err}");
+            texture.loading = None;
+        }
+        LoadState::NotLoaded | LoadState::Loading => {}
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::asset::embedded_asset;
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use serde::{Deserialize, Serialize};
+
+use crate::texture::PlanetSurfaceMaterial;
+use crate::{PlanetFace, PlanetMaterial, PlanetSettings, persistence};
+
+/// The shader quantizing the lighting into flat bands.
+const TOON_SHADER_PATH: &str = "embedded://bevy_mesh/shaders/toon.wgsl";
+
+/// How the planet surface is lit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Shading {
+    /// Bevy's physically based lighting, with the procedural texture.
+    #[default]
+    Pbr,
+    /// Cel shading in flat bands, with an optional outline.
+    Toon,
+}
+
+/// The settings of the cel-shaded look.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToonShading {
+    /// How many flat bands the lit side is split into.
+    pub bands: u32,
+    /// How far in from the silhouette the outline reaches, as the cosine of the view angle;
+    /// 0 disables it.
+    pub outline_width: f32,
+    #[serde(with = "persistence::srgba")]
+    pub outline_color: Color,
+}
+
+impl Default for ToonShading {
+    fn default() -> Self {
+        Self {
+            bands: 3,
+            outline_width: 0.2,
+            outline_color: Color::BLACK,
+        }
+    }
+}
+
+/// A material shading the surface in flat bands of the strongest directional light.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct ToonMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    #[uniform(0)]
+    pub outline_color: LinearRgba,
+    #[uniform(0)]
+    pub bands: u32,
+    #[uniform(0)]
+    pub outline_width: f32,
+}
+
+impl ToonMaterial {
+    fn new(toon: &ToonShading, color: Color) -> Self {
+        Self {
+            color: color.into(),
+            outline_color: toon.outline_color.into(),
+            bands: toon.bands,
+            outline_width: toon.outline_width,
+        }
+    }
+}
+
+impl Material for ToonMaterial {
+    fn fragment_shader() -> ShaderRef {
+        TOON_SHADER_PATH.into()
+    }
+}
+
+/// A resource holding the toon material shared by the planet faces.
+#[derive(Resource)]
+struct ToonPlanetMaterial(Handle<ToonMaterial>);
+
+pub struct ToonPlugin;
+
+impl Plugin for ToonPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/toon.wgsl");
+        app.add_plugins(MaterialPlugin::<ToonMaterial>::default())
+            .add_systems(Startup, setup_toon_material)
+            .add_systems(Update, apply_shading);
+    }
+}
+
+fn setup_toon_material(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ToonMaterial>>,
+    settings: Res<PlanetSettings>,
+) {
+    let material = materials.add(ToonMaterial::new(&settings.toon, settings.color));
+    commands.insert_resource(ToonPlanetMaterial(material));
+}
+
+/// Updates the toon material and swaps the faces between it and the PBR material.
+fn apply_shading(
+    mut commands: Commands,
+    settings: Res<PlanetSettings>,
+    planet_material: Res<PlanetMaterial>,
+    toon_material: Res<ToonPlanetMaterial>,
+    mut materials: ResMut<Assets<ToonMaterial>>,
+    q_faces: Query<(Entity, Has<MeshMaterial3d<ToonMaterial>>), With<PlanetFace>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    if let Some(material) = materials.get_mut(&toon_material.0) {
+        *material = ToonMaterial::new(&settings.toon, settings.color);
+    }
+    let toon = settings.shading == Shading::Toon;
+    for (entity, has_toon) in &q_faces {
+        if toon && !has_toon {
+            commands
+                .entity(entity)
+                .remove::<MeshMaterial3d<PlanetSurfaceMaterial>>()
+                .insert(MeshMaterial3d(toon_material.0.clone()));
+        } else if !toon && has_toon {
+            commands
+                .entity(entity)
+                .remove::<MeshMaterial3d<ToonMaterial>>()
+                .insert(MeshMaterial3d(planet_material.0.clone()));
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use bevy::asset::embedded_asset;
+use bevy::pbr::{
+    ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline,
+    MaterialPipeline, MaterialPipelineKey, NotShadowCaster,
+};
+use bevy::prelude::*;
+use bevy::render::{
+    mesh::{
+        Indices, MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology,
+        VertexAttributeValues,
+    },
+    render_asset::RenderAssetUsages,
+    render_resource::{
+        AsBindGroup, PolygonMode, RenderPipelineDescriptor, ShaderDefVal, ShaderRef,
+        SpecializedMeshPipelineError, VertexFormat,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{PlanetFace, PlanetSettings};
+
+/// The shader drawing antialiased edges from per-vertex barycentric coordinates.
+const BARYCENTRIC_SHADER_PATH: &str = "embedded://bevy_mesh/shaders/barycentric_wireframe.wgsl";
+
+/// The position of each vertex within its triangle, one corner per component.
+const ATTRIBUTE_BARYCENTRIC: MeshVertexAttribute =
+    MeshVertexAttribute::new("Barycentric", 613_784_209, VertexFormat::Float32x3);
+
+/// How the wireframe overlay is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireframeMode {
+    /// Rasterizes the triangle edges as one pixel lines.
+    #[default]
+    Lines,
+    /// Shades edges in the fragment shader with a constant width in pixels, regardless of mesh density.
+    Barycentric,
+}
+
+/// An unlit material drawn as lines, with the depth bias taken from the base material.
+///
+/// The lines take their color from the material alone, ignoring the vertex colors that ice caps
+/// and elevation colors put on the face meshes.
+pub type WireframeLineMaterial = ExtendedMaterial<StandardMaterial, LineMode>;
+
+/// A material extension that rasterizes triangles as their edges.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct LineMode {}
+
+impl MaterialExtension for LineMode {
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.polygon_mode = PolygonMode::Line;
+        let vertex_colors = ShaderDefVal::from("VERTEX_COLORS");
+        descriptor
+            .vertex
+            .shader_defs
+            .retain(|def| *def != vertex_colors);
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader_defs.retain(|def| *def != vertex_colors);
+        }
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.bias.slope_scale = 1.0;
+        }
+        Ok(())
+    }
+}
+
+/// An unlit material drawing antialiased triangle edges of a fixed width in pixels.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[bind_group_data(BarycentricWireKey)]
+pub struct BarycentricWireMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    #[uniform(0)]
+    pub width: f32,
+    pub depth_bias: f32,
+}
+
+/// The pipeline key of [`BarycentricWireMaterial`], carrying its depth bias.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BarycentricWireKey {
+    depth_bias: i32,
+}
+
+impl From<&BarycentricWireMaterial> for BarycentricWireKey {
+    fn from(material: &BarycentricWireMaterial) -> Self {
+        Self {
+            depth_bias: material.depth_bias as i32,
+        }
+    }
+}
+
+impl Material for BarycentricWireMaterial {
+    fn vertex_shader() -> ShaderRef {
+        BARYCENTRIC_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        BARYCENTRIC_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn depth_bias(&self) -> f32 {
+        self.depth_bias
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_BARYCENTRIC.at_shader_location(1),
+        ])?];
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.bias.constant = key.bind_group_data.depth_bias;
+            depth_stencil.bias.slope_scale = 1.0;
+        }
+        Ok(())
+    }
+}
+
+/// A resource to hold the handles to the materials shared by all wireframe overlays.
+#[derive(Resource)]
+struct WireframeOverlayMaterial {
+    lines: Handle<WireframeLineMaterial>,
+    barycentric: Handle<BarycentricWireMaterial>,
+}
+
+/// A component to identify a wireframe overlay drawn on top of a planet face.
+#[derive(Component)]
+struct WireframeOverlay {
+    mode: WireframeMode,
+}
+
+/// The face mesh a barycentric overlay's expanded mesh was last built from.
+#[derive(Component, Default)]
+struct BarycentricSource(Option<AssetId<Mesh>>);
+
+pub struct WireframeOverlayPlugin;
+
+impl Plugin for WireframeOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "shaders/barycentric_wireframe.wgsl");
+        app.add_plugins((
+            MaterialPlugin::<WireframeLineMaterial>::default(),
+            MaterialPlugin::<BarycentricWireMaterial>::default(),
+        ))
+        .add_systems(Startup, setup_wireframe_material)
+        .add_systems(
+            Update,
+            (
+                attach_wireframe_overlays,
+                sync_wireframe_meshes,
+                sync_barycentric_meshes,
+                apply_wireframe_settings,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn setup_wireframe_material(
+    mut commands: Commands,
+    mut line_materials: ResMut<Assets<WireframeLineMaterial>>,
+    mut barycentric_materials: ResMut<Assets<BarycentricWireMaterial>>,
+    settings: Res<PlanetSettings>,
+) {
+    let lines = line_materials.add(ExtendedMaterial {
+        base: StandardMaterial {
+            base_color: settings.wireframe_color,
+            unlit: true,
+            depth_bias: settings.wireframe_depth_bias,
+            ..default()
+        },
+        extension: LineMode::default(),
+    });
+    let barycentric = barycentric_materials.add(BarycentricWireMaterial {
+        color: settings.wireframe_color.into(),
+        width: settings.wireframe_width,
+        depth_bias: settings.wireframe_depth_bias,
+    });
+    commands.insert_resource(WireframeOverlayMaterial { lines, barycentric });
+}
+
+/// Spawns a line-mode copy and a barycentric overlay of every new planet face as its children.
+fn attach_wireframe_overlays(
+    mut commands: Commands,
+    material: Res<WireframeOverlayMaterial>,
+    settings: Res<PlanetSettings>,
+    q_faces: Query<(Entity, &Mesh3d), Added<PlanetFace>>,
+) {
+    for (entity, mesh_3d) in &q_faces {
+        let lines = commands
+            .spawn((
+                mesh_3d.clone(),
+                MeshMaterial3d(material.lines.clone()),
+                Transform::default(),
+                overlay_visibility(&settings, WireframeMode::Lines),
+                NotShadowCaster,
+                WireframeOverlay {
+                    mode: WireframeMode::Lines,
+                },
+            ))
+            .id();
+        // The expanded mesh is only built once the overlay is first shown.
+        let barycentric = commands
+            .spawn((
+                MeshMaterial3d(material.barycentric.clone()),
+                Transform::default(),
+                overlay_visibility(&settings, WireframeMode::Barycentric),
+                NotShadowCaster,
+                WireframeOverlay {
+                    mode: WireframeMode::Barycentric,
+                },
+                BarycentricSource::default(),
+            ))
+            .id();
+        commands.entity(entity).add_children(&[lines, barycentric]);
+    }
+}
+
+/// Keeps each overlay drawing the same mesh as its face after regeneration.
+#[allow(clippy::type_complexity)]
+fn sync_wireframe_meshes(
+    q_faces: Query<(&Mesh3d, &Children), (With<PlanetFace>, Changed<Mesh3d>)>,
+    mut q_overlays: Query<(&mut Mesh3d, &WireframeOverlay), Without<PlanetFace>>,
+) {
+    for (mesh_3d, children) in &q_faces {
+        for child in children {
+            if let Ok((mut overlay_mesh, overlay)) = q_overlays.get_mut(*child)
+                && overlay.mode == WireframeMode::Lines
+            {
+                *overlay_mesh = mesh_3d.clone();
+            }
+        }
+    }
+}
+
+/// Rebuilds the expanded mesh of each shown barycentric overlay whose face mesh changed.
+fn sync_barycentric_meshes(
+    mut commands: Commands,
+    settings: Res<PlanetSettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    q_faces: Query<(&Mesh3d, &Children), With<PlanetFace>>,
+    mut q_overlays: Query<&mut BarycentricSource>,
+) {
+    if !settings.wireframe || settings.wireframe_mode != WireframeMode::Barycentric {
+        return;
+    }
+    for (mesh_3d, children) in &q_faces {
+        for child in children {
+            let Ok(mut source) = q_overlays.get_mut(*child) else {
+                continue;
+            };
+            if source.0 == Some(mesh_3d.id()) {
+                continue;
+            }
+            let Some(mesh) = meshes.get(mesh_3d).and_then(barycentric_mesh) else {
+                continue;
+            };
+            source.0 = Some(mesh_3d.id());
+            commands.entity(*child).insert(Mesh3d(meshes.add(mesh)));
+        }
+    }
+}
+
+/// Expands an indexed triangle mesh so every triangle has its own corners tagged with barycentrics.
+fn barycentric_mesh(mesh: &Mesh) -> Option<Mesh> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        return None;
+    };
+
+    let corners = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let expanded: Vec<[f32; 3]> = indices.iter().map(|&i| positions[i as usize]).collect();
+    let barycentrics: Vec<[f32; 3]> = (0..indices.len()).map(|i| corners[i % 3]).collect();
+
+    let mut expanded_mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    expanded_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, expanded);
+    expanded_mesh.insert_attribute(ATTRIBUTE_BARYCENTRIC, barycentrics);
+    Some(expanded_mesh)
+}
+
+/// Shows or hides the overlays and updates their materials when settings change.
+fn apply_wireframe_settings(
+    settings: Res<PlanetSettings>,
+    material: Res<WireframeOverlayMaterial>,
+    mut line_materials: ResMut<Assets<WireframeLineMaterial>>,
+    mut barycentric_materials: ResMut<Assets<BarycentricWireMaterial>>,
+    mut q_overlays: Query<(&mut Visibility, &WireframeOverlay)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (mut visibility, overlay) in &mut q_overlays {
+        visibility.set_if_neq(overlay_visibility(&settings, overlay.mode));
+    }
+    if let Some(line_material) = line_materials.get_mut(&material.lines)
+        && (line_material.base.depth_bias != settings.wireframe_depth_bias
+            || line_material.base.base_color != settings.wireframe_color)
+    {
+        line_material.base.depth_bias = settings.wireframe_depth_bias;
+        line_material.base.base_color = settings.wireframe_color;
+    }
+    let color = LinearRgba::from(settings.wireframe_color);
+    if let Some(barycentric_material) = barycentric_materials.get_mut(&material.barycentric)
+        && (barycentric_material.depth_bias != settings.wireframe_depth_bias
+            || barycentric_material.color != color
+            || barycentric_material.width != settings.wireframe_width)
+    {
+        barycentric_material.depth_bias = settings.wireframe_depth_bias;
+        barycentric_material.color = color;
+        barycentric_material.width = settings.wireframe_width;
+    }
+}
+
+fn overlay_visibility(settings: &PlanetSettings, mode: WireframeMode) -> Visibility {
+    if settings.wireframe && settings.wireframe_mode == mode {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}